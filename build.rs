@@ -21,12 +21,17 @@ fn main() {
         },
     )
     .expect("failed to build xkb.pspec");
-    // protospec_build::compile_spec("xinput1", include_str!("./spec/xinput1.pspec"), &protospec_build::Options {
-    //     include_async: false,
-    //     use_anyhow: true,
-    //     // debug_mode: true,
-    //     ..Default::default()
-    // }).expect("failed to build xinput1.pspec");
+    protospec_build::compile_spec(
+        "xinput1",
+        include_str!("./spec/xinput1.pspec"),
+        &protospec_build::Options {
+            include_async: false,
+            use_anyhow: true,
+            // debug_mode: true,
+            ..Default::default()
+        },
+    )
+    .expect("failed to build xinput1.pspec");
     protospec_build::compile_spec(
         "xinput2",
         include_str!("./spec/xinput2.pspec"),
@@ -82,4 +87,147 @@ fn main() {
         },
     )
     .expect("failed to build xrecord.pspec");
+    protospec_build::compile_spec(
+        "render",
+        include_str!("./spec/render.pspec"),
+        &protospec_build::Options {
+            include_async: false,
+            use_anyhow: true,
+            // debug_mode: true,
+            ..Default::default()
+        },
+    )
+    .expect("failed to build render.pspec");
+    protospec_build::compile_spec(
+        "shm",
+        include_str!("./spec/shm.pspec"),
+        &protospec_build::Options {
+            include_async: false,
+            use_anyhow: true,
+            // debug_mode: true,
+            ..Default::default()
+        },
+    )
+    .expect("failed to build shm.pspec");
+    protospec_build::compile_spec(
+        "dpms",
+        include_str!("./spec/dpms.pspec"),
+        &protospec_build::Options {
+            include_async: false,
+            use_anyhow: true,
+            // debug_mode: true,
+            ..Default::default()
+        },
+    )
+    .expect("failed to build dpms.pspec");
+    protospec_build::compile_spec(
+        "dri2",
+        include_str!("./spec/dri2.pspec"),
+        &protospec_build::Options {
+            include_async: false,
+            use_anyhow: true,
+            // debug_mode: true,
+            ..Default::default()
+        },
+    )
+    .expect("failed to build dri2.pspec");
+    protospec_build::compile_spec(
+        "composite",
+        include_str!("./spec/composite.pspec"),
+        &protospec_build::Options {
+            include_async: false,
+            use_anyhow: true,
+            // debug_mode: true,
+            ..Default::default()
+        },
+    )
+    .expect("failed to build composite.pspec");
+    protospec_build::compile_spec(
+        "damage",
+        include_str!("./spec/damage.pspec"),
+        &protospec_build::Options {
+            include_async: false,
+            use_anyhow: true,
+            // debug_mode: true,
+            ..Default::default()
+        },
+    )
+    .expect("failed to build damage.pspec");
+    protospec_build::compile_spec(
+        "present",
+        include_str!("./spec/present.pspec"),
+        &protospec_build::Options {
+            include_async: false,
+            use_anyhow: true,
+            // debug_mode: true,
+            ..Default::default()
+        },
+    )
+    .expect("failed to build present.pspec");
+    protospec_build::compile_spec(
+        "sync",
+        include_str!("./spec/sync.pspec"),
+        &protospec_build::Options {
+            include_async: false,
+            use_anyhow: true,
+            // debug_mode: true,
+            ..Default::default()
+        },
+    )
+    .expect("failed to build sync.pspec");
+    protospec_build::compile_spec(
+        "xtest",
+        include_str!("./spec/xtest.pspec"),
+        &protospec_build::Options {
+            include_async: false,
+            use_anyhow: true,
+            // debug_mode: true,
+            ..Default::default()
+        },
+    )
+    .expect("failed to build xtest.pspec");
+    protospec_build::compile_spec(
+        "xinerama",
+        include_str!("./spec/xinerama.pspec"),
+        &protospec_build::Options {
+            include_async: false,
+            use_anyhow: true,
+            // debug_mode: true,
+            ..Default::default()
+        },
+    )
+    .expect("failed to build xinerama.pspec");
+    protospec_build::compile_spec(
+        "screensaver",
+        include_str!("./spec/screensaver.pspec"),
+        &protospec_build::Options {
+            include_async: false,
+            use_anyhow: true,
+            // debug_mode: true,
+            ..Default::default()
+        },
+    )
+    .expect("failed to build screensaver.pspec");
+    protospec_build::compile_spec(
+        "xres",
+        include_str!("./spec/xres.pspec"),
+        &protospec_build::Options {
+            include_async: false,
+            use_anyhow: true,
+            // debug_mode: true,
+            ..Default::default()
+        },
+    )
+    .expect("failed to build xres.pspec");
+    protospec_build::compile_spec(
+        "dri3",
+        include_str!("./spec/dri3.pspec"),
+        &protospec_build::Options {
+            include_async: false,
+            use_anyhow: true,
+            // debug_mode: true,
+            ..Default::default()
+        },
+    )
+    .expect("failed to build dri3.pspec");
 }
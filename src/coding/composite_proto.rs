@@ -0,0 +1,3 @@
+pub mod composite {
+    protospec::include_spec!("composite");
+}
@@ -0,0 +1,3 @@
+pub mod damage {
+    protospec::include_spec!("damage");
+}
@@ -0,0 +1,3 @@
+pub mod dpms {
+    protospec::include_spec!("dpms");
+}
@@ -0,0 +1,3 @@
+pub mod dri2 {
+    protospec::include_spec!("dri2");
+}
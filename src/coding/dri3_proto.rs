@@ -0,0 +1,3 @@
+pub mod dri3 {
+    protospec::include_spec!("dri3");
+}
@@ -18,3 +18,42 @@ pub use shape_proto::*;
 
 mod xrecord_proto;
 pub use xrecord_proto::*;
+
+mod render_proto;
+pub use render_proto::*;
+
+mod shm_proto;
+pub use shm_proto::*;
+
+mod dpms_proto;
+pub use dpms_proto::*;
+
+mod dri2_proto;
+pub use dri2_proto::*;
+
+mod composite_proto;
+pub use composite_proto::*;
+
+mod damage_proto;
+pub use damage_proto::*;
+
+mod present_proto;
+pub use present_proto::*;
+
+mod sync_proto;
+pub use sync_proto::*;
+
+mod xtest_proto;
+pub use xtest_proto::*;
+
+mod xinerama_proto;
+pub use xinerama_proto::*;
+
+mod screensaver_proto;
+pub use screensaver_proto::*;
+
+mod xres_proto;
+pub use xres_proto::*;
+
+mod dri3_proto;
+pub use dri3_proto::*;
@@ -0,0 +1,3 @@
+pub mod present {
+    protospec::include_spec!("present");
+}
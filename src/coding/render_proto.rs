@@ -0,0 +1,3 @@
+pub mod render {
+    protospec::include_spec!("render");
+}
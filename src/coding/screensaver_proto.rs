@@ -0,0 +1,3 @@
+pub mod screensaver {
+    protospec::include_spec!("screensaver");
+}
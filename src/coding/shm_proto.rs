@@ -0,0 +1,3 @@
+pub mod shm {
+    protospec::include_spec!("shm");
+}
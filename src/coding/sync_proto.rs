@@ -0,0 +1,3 @@
+pub mod sync {
+    protospec::include_spec!("sync");
+}
@@ -0,0 +1,3 @@
+pub mod xinerama {
+    protospec::include_spec!("xinerama");
+}
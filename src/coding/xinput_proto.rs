@@ -1,8 +1,8 @@
 use self::xinput2::{XIEventCode, XIEventData};
 
-// pub mod xinput1 {
-//     protospec::include_spec!("xinput1");
-// }
+pub mod xinput1 {
+    protospec::include_spec!("xinput1");
+}
 pub mod xinput2 {
     protospec::include_spec!("xinput2");
 }
@@ -0,0 +1,3 @@
+pub mod xres {
+    protospec::include_spec!("xres");
+}
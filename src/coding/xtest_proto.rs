@@ -0,0 +1,3 @@
+pub mod xtest {
+    protospec::include_spec!("xtest");
+}
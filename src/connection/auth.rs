@@ -0,0 +1,270 @@
+use std::{env, ffi::CStr, net::IpAddr, path::PathBuf};
+
+use anyhow::{Context, Result};
+
+pub const MIT_MAGIC_COOKIE_1: &str = "MIT-MAGIC-COOKIE-1";
+
+/// Authorization protocols we know how to send, in order of preference
+const SUPPORTED_AUTH_NAMES: &[&str] = &[MIT_MAGIC_COOKIE_1];
+
+/// Authorization sent to the X server in the connection handshake
+#[derive(Clone, derivative::Derivative)]
+#[derivative(Debug)]
+pub struct AuthInfo {
+    pub name: String,
+    #[derivative(Debug = "ignore")]
+    pub data: Vec<u8>,
+}
+
+impl AuthInfo {
+    pub fn mit_magic_cookie(cookie: impl Into<Vec<u8>>) -> Self {
+        Self {
+            name: MIT_MAGIC_COOKIE_1.to_string(),
+            data: cookie.into(),
+        }
+    }
+
+    /// Looks up the best matching entry for a local (unix socket or loopback) connection to `display`
+    pub async fn from_xauthority_local(display: u16) -> Result<Option<AuthInfo>> {
+        Self::from_xauthority(XauthFamily::Local, hostname()?.as_bytes(), display).await
+    }
+
+    /// Looks up the best matching entry for a TCP connection to `addr`
+    pub async fn from_xauthority_tcp(addr: IpAddr, display: u16) -> Result<Option<AuthInfo>> {
+        if addr.is_loopback() {
+            return Self::from_xauthority_local(display).await;
+        }
+        match addr {
+            IpAddr::V4(v4) => Self::from_xauthority(XauthFamily::Internet, &v4.octets(), display).await,
+            IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+                Some(v4) => Self::from_xauthority(XauthFamily::Internet, &v4.octets(), display).await,
+                None => Self::from_xauthority(XauthFamily::Internet6, &v6.octets(), display).await,
+            },
+        }
+    }
+
+    pub async fn from_xauthority(family: XauthFamily, address: &[u8], display: u16) -> Result<Option<AuthInfo>> {
+        let path = match xauthority_path() {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+        let raw = match tokio::fs::read(&path).await {
+            Ok(x) => x,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+        };
+        let entries = XauthEntry::parse_all(&raw[..]).with_context(|| format!("malformed xauthority file {}", path.display()))?;
+        Ok(XauthEntry::find_best(&entries, family, address, display).map(|entry| AuthInfo {
+            name: entry.name.clone(),
+            data: entry.data.clone(),
+        }))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum XauthFamily {
+    Internet,
+    DecNet,
+    Chaos,
+    Internet6,
+    Local,
+    Wild,
+    Unknown(u16),
+}
+
+impl XauthFamily {
+    fn from_raw(raw: u16) -> Self {
+        match raw {
+            0 => XauthFamily::Internet,
+            1 => XauthFamily::DecNet,
+            2 => XauthFamily::Chaos,
+            6 => XauthFamily::Internet6,
+            256 => XauthFamily::Local,
+            65535 => XauthFamily::Wild,
+            x => XauthFamily::Unknown(x),
+        }
+    }
+}
+
+/// A single entry of an Xauthority file
+#[derive(Clone, derivative::Derivative)]
+#[derivative(Debug)]
+pub struct XauthEntry {
+    pub family: XauthFamily,
+    pub address: Vec<u8>,
+    pub display: String,
+    pub name: String,
+    #[derivative(Debug = "ignore")]
+    pub data: Vec<u8>,
+}
+
+impl XauthEntry {
+    pub fn parse_all(mut data: &[u8]) -> Result<Vec<XauthEntry>> {
+        let mut out = vec![];
+        while !data.is_empty() {
+            out.push(Self::parse(&mut data)?);
+        }
+        Ok(out)
+    }
+
+    fn parse(data: &mut &[u8]) -> Result<XauthEntry> {
+        let family = XauthFamily::from_raw(read_u16(data)?);
+        let address = read_counted(data)?;
+        let display = String::from_utf8(read_counted(data)?).context("invalid display number")?;
+        let name = String::from_utf8(read_counted(data)?).context("invalid auth name")?;
+        let data = read_counted(data)?;
+        Ok(XauthEntry {
+            family,
+            address,
+            display,
+            name,
+            data,
+        })
+    }
+
+    fn matches(&self, family: XauthFamily, address: &[u8], display: &str) -> bool {
+        if self.family != XauthFamily::Wild && (self.family != family || self.address != address) {
+            return false;
+        }
+        self.display.is_empty() || self.display == display
+    }
+
+    /// Picks the first matching entry of the most preferred supported protocol, like `XauGetBestAuthByAddr`
+    pub fn find_best<'a>(entries: &'a [XauthEntry], family: XauthFamily, address: &[u8], display: u16) -> Option<&'a XauthEntry> {
+        let display = display.to_string();
+        let mut best: Option<(usize, &XauthEntry)> = None;
+        for entry in entries {
+            if !entry.matches(family, address, &display) {
+                continue;
+            }
+            let rank = match SUPPORTED_AUTH_NAMES.iter().position(|name| *name == entry.name) {
+                Some(x) => x,
+                None => continue,
+            };
+            if best.map(|(best_rank, _)| rank < best_rank).unwrap_or(true) {
+                best = Some((rank, entry));
+            }
+        }
+        best.map(|(_, entry)| entry)
+    }
+}
+
+fn read_u16(data: &mut &[u8]) -> Result<u16> {
+    if data.len() < 2 {
+        bail!("unexpected end of xauthority data");
+    }
+    let out = u16::from_be_bytes([data[0], data[1]]);
+    *data = &data[2..];
+    Ok(out)
+}
+
+fn read_counted(data: &mut &[u8]) -> Result<Vec<u8>> {
+    let len = read_u16(data)? as usize;
+    if data.len() < len {
+        bail!("unexpected end of xauthority data");
+    }
+    let out = data[..len].to_vec();
+    *data = &data[len..];
+    Ok(out)
+}
+
+/// `$XAUTHORITY`, falling back to `~/.Xauthority`
+pub fn xauthority_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("XAUTHORITY") {
+        if !path.is_empty() {
+            return Some(path.into());
+        }
+    }
+    let home = env::var_os("HOME")?;
+    Some(PathBuf::from(home).join(".Xauthority"))
+}
+
+fn hostname() -> Result<String> {
+    let mut buf = [0u8; 256];
+    let ret = unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) };
+    if ret < 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    // gethostname does not guarantee null termination on truncation
+    buf[buf.len() - 1] = 0;
+    let name = CStr::from_bytes_until_nul(&buf[..]).context("invalid hostname")?;
+    Ok(name.to_str().context("non-utf8 hostname")?.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(family: u16, address: &[u8], display: &str, name: &str, data: &[u8]) -> Vec<u8> {
+        let mut out = family.to_be_bytes().to_vec();
+        for field in [address, display.as_bytes(), name.as_bytes(), data] {
+            out.extend_from_slice(&(field.len() as u16).to_be_bytes());
+            out.extend_from_slice(field);
+        }
+        out
+    }
+
+    #[test]
+    fn parse_multiple_entries() {
+        let mut raw = entry(256, b"host", "0", MIT_MAGIC_COOKIE_1, &[1; 16]);
+        raw.extend(entry(0, &[10, 0, 0, 1], "1", "XDM-AUTHORIZATION-1", &[2; 8]));
+        raw.extend(entry(6, &[0; 16], "", MIT_MAGIC_COOKIE_1, &[]));
+
+        let entries = XauthEntry::parse_all(&raw).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].family, XauthFamily::Local);
+        assert_eq!(entries[0].address, b"host");
+        assert_eq!(entries[0].display, "0");
+        assert_eq!(entries[0].name, MIT_MAGIC_COOKIE_1);
+        assert_eq!(entries[0].data, [1; 16]);
+        assert_eq!(entries[1].family, XauthFamily::Internet);
+        assert_eq!(entries[1].address, [10, 0, 0, 1]);
+        assert_eq!(entries[1].name, "XDM-AUTHORIZATION-1");
+        assert_eq!(entries[2].family, XauthFamily::Internet6);
+        assert_eq!(entries[2].display, "");
+        assert!(entries[2].data.is_empty());
+    }
+
+    #[test]
+    fn wild_family_matches_any_address() {
+        let raw = entry(65535, b"", "0", MIT_MAGIC_COOKIE_1, &[3; 16]);
+        let entries = XauthEntry::parse_all(&raw).unwrap();
+        assert_eq!(entries[0].family, XauthFamily::Wild);
+
+        let best = XauthEntry::find_best(&entries, XauthFamily::Internet, &[192, 168, 0, 1], 0).unwrap();
+        assert_eq!(best.data, [3; 16]);
+        assert!(XauthEntry::find_best(&entries, XauthFamily::Local, b"other", 0).is_some());
+        assert!(XauthEntry::find_best(&entries, XauthFamily::Local, b"other", 1).is_none());
+    }
+
+    #[test]
+    fn matches_display_number() {
+        let mut raw = entry(256, b"host", "0", MIT_MAGIC_COOKIE_1, &[0; 16]);
+        raw.extend(entry(256, b"host", "1", MIT_MAGIC_COOKIE_1, &[1; 16]));
+        raw.extend(entry(256, b"other", "2", MIT_MAGIC_COOKIE_1, &[2; 16]));
+        let entries = XauthEntry::parse_all(&raw).unwrap();
+
+        assert_eq!(XauthEntry::find_best(&entries, XauthFamily::Local, b"host", 0).unwrap().data, [0; 16]);
+        assert_eq!(XauthEntry::find_best(&entries, XauthFamily::Local, b"host", 1).unwrap().data, [1; 16]);
+        assert!(XauthEntry::find_best(&entries, XauthFamily::Local, b"host", 2).is_none());
+        assert!(XauthEntry::find_best(&entries, XauthFamily::Internet, b"host", 0).is_none());
+    }
+
+    #[test]
+    fn skips_unsupported_protocols() {
+        let mut raw = entry(256, b"host", "0", "XDM-AUTHORIZATION-1", &[0; 8]);
+        raw.extend(entry(256, b"host", "0", MIT_MAGIC_COOKIE_1, &[1; 16]));
+        let entries = XauthEntry::parse_all(&raw).unwrap();
+
+        assert_eq!(XauthEntry::find_best(&entries, XauthFamily::Local, b"host", 0).unwrap().name, MIT_MAGIC_COOKIE_1);
+    }
+
+    #[test]
+    fn truncated_file_is_an_error() {
+        let raw = entry(256, b"host", "0", MIT_MAGIC_COOKIE_1, &[1; 16]);
+        for len in 1..raw.len() {
+            assert!(XauthEntry::parse_all(&raw[..len]).is_err(), "truncated at {}", len);
+        }
+        assert!(XauthEntry::parse_all(&[]).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,141 @@
+use std::{env, fmt, str::FromStr};
+
+use anyhow::{Context, Result};
+
+/// A parsed X display name of the form `[protocol/][host]:display[.screen]`, or an absolute socket path
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DisplayName {
+    pub protocol: Option<String>,
+    pub host: String,
+    pub display: u16,
+    pub screen: u16,
+    /// set when the display name was an absolute socket path (i.e. launchd or `/tmp/.X11-unix/X0`)
+    pub socket_path: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisplayTransport {
+    Unix,
+    Tcp,
+}
+
+impl DisplayName {
+    pub fn new(host: &str, display: u16) -> Self {
+        Self {
+            protocol: None,
+            host: host.to_string(),
+            display,
+            screen: 0,
+            socket_path: None,
+        }
+    }
+
+    /// Parses `$DISPLAY`
+    pub fn from_env() -> Result<Self> {
+        let display = env::var("DISPLAY").context("DISPLAY is not set")?;
+        Self::parse(&display)
+    }
+
+    pub fn parse(name: &str) -> Result<Self> {
+        if name.starts_with('/') {
+            return Self::parse_path(name);
+        }
+
+        let colon = name.rfind(':').ok_or_else(|| anyhow!("missing ':' in display name '{}'", name))?;
+        let (prefix, suffix) = (&name[..colon], &name[colon + 1..]);
+        let (display, screen) = parse_display_screen(suffix).with_context(|| format!("invalid display name '{}'", name))?;
+
+        let (protocol, host) = match prefix.find('/') {
+            Some(slash) => (Some(prefix[..slash].to_string()), &prefix[slash + 1..]),
+            None => (None, prefix),
+        };
+        // bracketed IPv6 literal
+        let host = host.strip_prefix('[').and_then(|x| x.strip_suffix(']')).unwrap_or(host);
+
+        Ok(Self {
+            protocol,
+            host: host.to_string(),
+            display,
+            screen,
+            socket_path: None,
+        })
+    }
+
+    fn parse_path(name: &str) -> Result<Self> {
+        let (path, display, screen) = match name.rfind(':') {
+            // launchd style, the socket itself is named `...:<display>`
+            Some(colon) if colon > name.rfind('/').unwrap_or(0) => {
+                let (display, screen) = parse_display_screen(&name[colon + 1..]).with_context(|| format!("invalid display name '{}'", name))?;
+                let path = match name[colon..].find('.') {
+                    Some(dot) => &name[..colon + dot],
+                    None => name,
+                };
+                (path, display, screen)
+            }
+            _ => {
+                // `/tmp/.X11-unix/X<display>`
+                let file_name = &name[name.rfind('/').unwrap_or(0) + 1..];
+                let display = file_name.strip_prefix('X').and_then(|x| x.parse::<u16>().ok()).unwrap_or(0);
+                (name, display, 0)
+            }
+        };
+        Ok(Self {
+            protocol: None,
+            host: String::new(),
+            display,
+            screen,
+            socket_path: Some(path.to_string()),
+        })
+    }
+
+    pub fn transport(&self) -> Result<DisplayTransport> {
+        if self.socket_path.is_some() {
+            return Ok(DisplayTransport::Unix);
+        }
+        match self.protocol.as_deref() {
+            Some("unix") | Some("local") => Ok(DisplayTransport::Unix),
+            Some("tcp") | Some("inet") | Some("inet6") => Ok(DisplayTransport::Tcp),
+            Some(protocol) => bail!("unsupported display protocol '{}'", protocol),
+            None if self.host.is_empty() || self.host == "unix" => Ok(DisplayTransport::Unix),
+            None => Ok(DisplayTransport::Tcp),
+        }
+    }
+}
+
+fn parse_display_screen(raw: &str) -> Result<(u16, u16)> {
+    let (display, screen) = match raw.split_once('.') {
+        Some((display, screen)) => (display, Some(screen)),
+        None => (raw, None),
+    };
+    let display = display.parse::<u16>().context("invalid display number")?;
+    let screen = match screen {
+        Some(screen) => screen.parse::<u16>().context("invalid screen number")?,
+        None => 0,
+    };
+    Ok((display, screen))
+}
+
+impl FromStr for DisplayName {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for DisplayName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(path) = &self.socket_path {
+            return write!(f, "{}", path);
+        }
+        if let Some(protocol) = &self.protocol {
+            write!(f, "{}/", protocol)?;
+        }
+        if self.host.contains(':') {
+            write!(f, "[{}]", self.host)?;
+        } else {
+            write!(f, "{}", self.host)?;
+        }
+        write!(f, ":{}.{}", self.display, self.screen)
+    }
+}
@@ -0,0 +1,18 @@
+use std::{collections::VecDeque, sync::Mutex};
+
+#[cfg(not(target_os = "windows"))]
+pub use std::os::unix::io::OwnedFd;
+
+/// File descriptors can't be passed on windows
+#[cfg(target_os = "windows")]
+#[derive(Debug)]
+pub enum OwnedFd {}
+
+/// File descriptors passed alongside the byte stream of a unix socket
+#[derive(Default)]
+pub(crate) struct FdQueues {
+    // sent with the next write, which carries the bytes of the request they belong to (or earlier ones)
+    pub outgoing: Mutex<Vec<OwnedFd>>,
+    // received in order, until claimed by the reply they were sent with
+    pub incoming: Mutex<VecDeque<OwnedFd>>,
+}
@@ -6,4 +6,13 @@ pub use unix::*;
 mod tcp;
 pub use tcp::*;
 
+mod fd;
+pub use fd::*;
+
+mod auth;
+pub use auth::*;
+
+mod display;
+pub use display::*;
+
 use anyhow::Result;
@@ -19,6 +19,10 @@ impl TcpConnection {
         })
     }
 
+    pub fn peer_addr(&self) -> Result<SocketAddr> {
+        Ok(self.connection.peer_addr()?)
+    }
+
     pub fn into_split(self) -> (impl AsyncRead + Unpin + Send + Sync + 'static, impl AsyncWrite + Unpin + Send + Sync + 'static) {
         self.connection.into_split()
     }
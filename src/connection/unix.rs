@@ -1,7 +1,12 @@
+use futures::ready;
+use std::collections::VecDeque;
 use std::io::Error as IoError;
 use std::os::unix::net::UnixStream as StdUnixStream;
-use std::os::unix::prelude::FromRawFd;
-use tokio::io::{AsyncRead, AsyncWrite};
+use std::os::unix::prelude::{AsRawFd, FromRawFd, RawFd};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, Interest, ReadBuf};
 use tokio::net::UnixStream;
 
 use super::*;
@@ -26,8 +31,14 @@ impl UnixConnection {
         }
     }
 
+    /// Connects to an exact socket path, without appending a display number
+    pub async fn connect_socket(path: &str) -> Result<Self> {
+        Self::connect_unix(path).await
+    }
+
     async fn connect_unix(path: &str) -> Result<Self> {
         let socket = UnixStream::connect(path).await?;
+        info!("X11 connected at {}", path);
         Ok(UnixConnection {
             connection: socket,
         })
@@ -69,6 +80,172 @@ impl UnixConnection {
     pub fn into_split(self) -> (impl AsyncRead + Unpin + Send + Sync + 'static, impl AsyncWrite + Unpin + Send + Sync + 'static) {
         self.connection.into_split()
     }
+
+    /// Splits like [`UnixConnection::into_split`], but passes file descriptors through the returned queues
+    pub(crate) fn into_fd_split(self) -> (UnixReadHalf, UnixWriteHalf, Arc<FdQueues>) {
+        let stream = Arc::new(self.connection);
+        let fds = Arc::new(FdQueues::default());
+        (
+            UnixReadHalf {
+                stream: stream.clone(),
+                fds: fds.clone(),
+            },
+            UnixWriteHalf {
+                stream,
+                fds: fds.clone(),
+            },
+            fds,
+        )
+    }
+}
+
+pub(crate) struct UnixReadHalf {
+    stream: Arc<UnixStream>,
+    fds: Arc<FdQueues>,
+}
+
+pub(crate) struct UnixWriteHalf {
+    stream: Arc<UnixStream>,
+    fds: Arc<FdQueues>,
+}
+
+impl AsyncRead for UnixReadHalf {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            ready!(this.stream.poll_read_ready(cx))?;
+            let socket = this.stream.as_raw_fd();
+            let unfilled = buf.initialize_unfilled();
+            let mut incoming = this.fds.incoming.lock().unwrap();
+            match this.stream.try_io(Interest::READABLE, || recv_with_fds(socket, unfilled, &mut incoming)) {
+                Ok(read) => {
+                    drop(incoming);
+                    buf.advance(read);
+                    return Poll::Ready(Ok(()));
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => continue,
+                Err(e) => return Poll::Ready(Err(e)),
+            }
+        }
+    }
+}
+
+impl AsyncWrite for UnixWriteHalf {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, data: &[u8]) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        loop {
+            ready!(this.stream.poll_write_ready(cx))?;
+            let socket = this.stream.as_raw_fd();
+            let mut outgoing = this.fds.outgoing.lock().unwrap();
+            match this.stream.try_io(Interest::WRITABLE, || send_with_fds(socket, data, &outgoing)) {
+                Ok(written) => {
+                    // the kernel holds its own references now
+                    outgoing.clear();
+                    return Poll::Ready(Ok(written));
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => continue,
+                Err(e) => return Poll::Ready(Err(e)),
+            }
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let ret = unsafe { libc::shutdown(self.stream.as_raw_fd(), libc::SHUT_WR) };
+        if ret < 0 {
+            return Poll::Ready(Err(IoError::last_os_error()));
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+// most replies carry a single fd, leave plenty of room for batches of them
+const MAX_RECEIVED_FDS: usize = 64;
+
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd", target_os = "netbsd", target_os = "openbsd"))]
+const RECV_FLAGS: libc::c_int = libc::MSG_CMSG_CLOEXEC;
+// without MSG_CMSG_CLOEXEC, close-on-exec is set on each received fd instead
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "freebsd", target_os = "netbsd", target_os = "openbsd")))]
+const RECV_FLAGS: libc::c_int = 0;
+
+fn send_with_fds(socket: RawFd, data: &[u8], fds: &[OwnedFd]) -> std::io::Result<usize> {
+    let mut iov = libc::iovec {
+        iov_base: data.as_ptr() as *mut libc::c_void,
+        iov_len: data.len(),
+    };
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+
+    // u64 for cmsghdr alignment
+    let mut control: Vec<u64>;
+    if !fds.is_empty() {
+        let fds_len = (fds.len() * std::mem::size_of::<libc::c_int>()) as u32;
+        let space = unsafe { libc::CMSG_SPACE(fds_len) } as usize;
+        control = vec![0u64; (space + 7) / 8];
+        msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+        msg.msg_controllen = space as _;
+        unsafe {
+            let cmsg = libc::CMSG_FIRSTHDR(&msg);
+            (*cmsg).cmsg_level = libc::SOL_SOCKET;
+            (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+            (*cmsg).cmsg_len = libc::CMSG_LEN(fds_len) as _;
+            let out = libc::CMSG_DATA(cmsg) as *mut libc::c_int;
+            for (i, fd) in fds.iter().enumerate() {
+                out.add(i).write_unaligned(fd.as_raw_fd());
+            }
+        }
+    }
+
+    let ret = unsafe { libc::sendmsg(socket, &msg, 0) };
+    if ret < 0 {
+        return Err(IoError::last_os_error());
+    }
+    Ok(ret as usize)
+}
+
+fn recv_with_fds(socket: RawFd, buf: &mut [u8], fds: &mut VecDeque<OwnedFd>) -> std::io::Result<usize> {
+    let mut iov = libc::iovec {
+        iov_base: buf.as_mut_ptr() as *mut libc::c_void,
+        iov_len: buf.len(),
+    };
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    let space = unsafe { libc::CMSG_SPACE((MAX_RECEIVED_FDS * std::mem::size_of::<libc::c_int>()) as u32) } as usize;
+    let mut control = vec![0u64; (space + 7) / 8];
+    msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+    msg.msg_controllen = space as _;
+
+    let ret = unsafe { libc::recvmsg(socket, &mut msg, RECV_FLAGS) };
+    if ret < 0 {
+        return Err(IoError::last_os_error());
+    }
+    unsafe {
+        let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+        while !cmsg.is_null() {
+            if (*cmsg).cmsg_level == libc::SOL_SOCKET && (*cmsg).cmsg_type == libc::SCM_RIGHTS {
+                let data = libc::CMSG_DATA(cmsg) as *const libc::c_int;
+                let count = ((*cmsg).cmsg_len as usize - libc::CMSG_LEN(0) as usize) / std::mem::size_of::<libc::c_int>();
+                for i in 0..count {
+                    let fd = data.add(i).read_unaligned();
+                    if RECV_FLAGS == 0 {
+                        libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC);
+                    }
+                    fds.push_back(OwnedFd::from_raw_fd(fd));
+                }
+            }
+            cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+        }
+    }
+    // the dropped fds belong to replies we can no longer decode correctly
+    if msg.msg_flags & libc::MSG_CTRUNC != 0 {
+        return Err(IoError::new(std::io::ErrorKind::InvalidData, anyhow!("file descriptors received from the x11 server were truncated")));
+    }
+    Ok(ret as usize)
 }
 
 // copied from stdlib
@@ -0,0 +1,81 @@
+pub use crate::coding::damage::DamageEventMask;
+use crate::{
+    coding::damage::{self, DamageEventCode, DamageEventData, ReportLevel},
+    net::X11Connection,
+    requests::{Damage, Drawable, RawDrawable, Rectangle, Timestamp},
+};
+use anyhow::Result;
+
+#[derive(Clone, Debug)]
+pub enum DamageEvent<'a> {
+    Notify(DamageNotifyEvent<'a>),
+}
+
+impl<'a> DamageEvent<'a> {
+    pub(crate) fn code(&self) -> DamageEventCode {
+        match self {
+            DamageEvent::Notify(_) => DamageEventCode::Notify,
+        }
+    }
+
+    pub(crate) async fn from_protocol(connection: &'a X11Connection, from: Vec<u8>, code: u8) -> Result<DamageEvent<'a>> {
+        let event = DamageEventData::decode_sync(&mut &from[..], DamageEventCode::from_repr(code)?)?;
+        Ok(match event {
+            DamageEventData::Notify(e) => DamageEvent::Notify(DamageNotifyEvent::from_protocol(connection, e)?),
+        })
+    }
+
+    pub(crate) fn to_protocol(self) -> DamageEventData {
+        match self {
+            DamageEvent::Notify(e) => DamageEventData::Notify(e.to_protocol()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DamageNotifyEvent<'a> {
+    pub level: ReportLevel,
+    /// more events for the same damage follow
+    pub more: bool,
+    pub sequence_number: u16,
+    pub drawable: Drawable<'a>,
+    pub damage: Damage<'a>,
+    pub time: Timestamp,
+    /// damaged area, relative to the drawable
+    pub area: Rectangle,
+    /// geometry of the drawable
+    pub geometry: Rectangle,
+}
+
+impl<'a> DamageNotifyEvent<'a> {
+    fn from_protocol(connection: &'a X11Connection, event: damage::NotifyEvent) -> Result<DamageNotifyEvent<'a>> {
+        Ok(Self {
+            level: ReportLevel::from_repr(event.level & 0x7f)?,
+            more: event.level & 0x80 != 0,
+            sequence_number: event.sequence_number,
+            drawable: Drawable::Raw(RawDrawable {
+                handle: event.drawable,
+                connection,
+            }),
+            damage: Damage {
+                handle: event.damage,
+                connection,
+            },
+            time: Timestamp(event.time),
+            area: event.area.into(),
+            geometry: event.geometry.into(),
+        })
+    }
+
+    fn to_protocol(self) -> damage::NotifyEvent {
+        damage::NotifyEvent {
+            level: self.level as u8 | if self.more { 0x80 } else { 0 },
+            sequence_number: self.sequence_number,
+            drawable: self.drawable.handle(),
+            damage: self.damage.handle,
+            time: self.time.0,
+            area: self.area.into(),
+            geometry: self.geometry.into(),
+        }
+    }
+}
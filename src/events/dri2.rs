@@ -0,0 +1,100 @@
+pub use crate::coding::dri2::{BufferSwapEventType, DRI2EventMask};
+use crate::{
+    coding::dri2::{self, DRI2EventCode, DRI2EventData},
+    net::X11Connection,
+    requests::{Drawable, RawDrawable},
+};
+use anyhow::Result;
+
+#[derive(Clone, Debug)]
+pub enum DRI2Event<'a> {
+    BufferSwapComplete(BufferSwapCompleteEvent<'a>),
+    InvalidateBuffers(InvalidateBuffersEvent<'a>),
+}
+
+impl<'a> DRI2Event<'a> {
+    pub(crate) fn code(&self) -> DRI2EventCode {
+        match self {
+            DRI2Event::BufferSwapComplete(_) => DRI2EventCode::BufferSwapComplete,
+            DRI2Event::InvalidateBuffers(_) => DRI2EventCode::InvalidateBuffers,
+        }
+    }
+
+    pub(crate) async fn from_protocol(connection: &'a X11Connection, from: Vec<u8>, code: u8) -> Result<DRI2Event<'a>> {
+        let event = DRI2EventData::decode_sync(&mut &from[..], DRI2EventCode::from_repr(code)?)?;
+        Ok(match event {
+            DRI2EventData::BufferSwapComplete(e) => DRI2Event::BufferSwapComplete(BufferSwapCompleteEvent::from_protocol(connection, e)),
+            DRI2EventData::InvalidateBuffers(e) => DRI2Event::InvalidateBuffers(InvalidateBuffersEvent::from_protocol(connection, e)),
+        })
+    }
+
+    pub(crate) fn to_protocol(self) -> DRI2EventData {
+        match self {
+            DRI2Event::BufferSwapComplete(e) => DRI2EventData::BufferSwapComplete(e.to_protocol()),
+            DRI2Event::InvalidateBuffers(e) => DRI2EventData::InvalidateBuffers(e.to_protocol()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BufferSwapCompleteEvent<'a> {
+    pub sequence_number: u16,
+    pub event_type: BufferSwapEventType,
+    pub drawable: Drawable<'a>,
+    pub ust: u64,
+    pub msc: u64,
+    // only the low 32 bits are sent
+    pub sbc: u32,
+}
+
+impl<'a> BufferSwapCompleteEvent<'a> {
+    fn from_protocol(connection: &'a X11Connection, event: dri2::BufferSwapCompleteEvent) -> BufferSwapCompleteEvent<'a> {
+        Self {
+            sequence_number: event.sequence_number,
+            event_type: event.event_type,
+            drawable: Drawable::Raw(RawDrawable {
+                handle: event.drawable,
+                connection,
+            }),
+            ust: event.ust,
+            msc: event.msc,
+            sbc: event.sbc,
+        }
+    }
+
+    fn to_protocol(self) -> dri2::BufferSwapCompleteEvent {
+        dri2::BufferSwapCompleteEvent {
+            sequence_number: self.sequence_number,
+            event_type: self.event_type,
+            drawable: self.drawable.handle(),
+            ust: self.ust,
+            msc: self.msc,
+            sbc: self.sbc,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InvalidateBuffersEvent<'a> {
+    pub sequence_number: u16,
+    pub drawable: Drawable<'a>,
+}
+
+impl<'a> InvalidateBuffersEvent<'a> {
+    fn from_protocol(connection: &'a X11Connection, event: dri2::InvalidateBuffersEvent) -> InvalidateBuffersEvent<'a> {
+        Self {
+            sequence_number: event.sequence_number,
+            drawable: Drawable::Raw(RawDrawable {
+                handle: event.drawable,
+                connection,
+            }),
+        }
+    }
+
+    fn to_protocol(self) -> dri2::InvalidateBuffersEvent {
+        dri2::InvalidateBuffersEvent {
+            sequence_number: self.sequence_number,
+            drawable: self.drawable.handle(),
+        }
+    }
+}
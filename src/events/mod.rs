@@ -15,3 +15,21 @@ pub use xrandr::*;
 
 pub mod shape;
 pub use shape::*;
+
+pub mod dri2;
+pub use dri2::*;
+
+pub mod damage;
+pub use damage::*;
+
+pub mod present;
+pub use present::*;
+
+pub mod sync;
+pub use sync::*;
+
+pub mod screensaver;
+pub use screensaver::*;
+
+pub mod xinput1;
+pub use xinput1::*;
@@ -0,0 +1,188 @@
+pub use crate::coding::present::{CompleteKind, CompleteMode};
+use crate::{
+    coding::present::{self, PresentEventCode, PresentEventData},
+    net::X11Connection,
+    requests::{Fence, Pixmap, PresentEventSelection, Window},
+};
+use anyhow::Result;
+
+#[derive(Clone, Debug)]
+pub enum PresentEvent<'a> {
+    ConfigureNotify(PresentConfigureNotifyEvent<'a>),
+    CompleteNotify(CompleteNotifyEvent<'a>),
+    IdleNotify(IdleNotifyEvent<'a>),
+}
+
+impl<'a> PresentEvent<'a> {
+    pub(crate) fn code(&self) -> PresentEventCode {
+        match self {
+            PresentEvent::ConfigureNotify(_) => PresentEventCode::ConfigureNotify,
+            PresentEvent::CompleteNotify(_) => PresentEventCode::CompleteNotify,
+            PresentEvent::IdleNotify(_) => PresentEventCode::IdleNotify,
+        }
+    }
+
+    pub(crate) async fn from_protocol(connection: &'a X11Connection, code: u16, from: Vec<u8>) -> Result<PresentEvent<'a>> {
+        let event = PresentEventData::decode_sync(&mut &from[..], PresentEventCode::from_repr(code)?)?;
+        Ok(match event {
+            PresentEventData::ConfigureNotify(e) => PresentEvent::ConfigureNotify(PresentConfigureNotifyEvent::from_protocol(connection, e)),
+            PresentEventData::CompleteNotify(e) => PresentEvent::CompleteNotify(CompleteNotifyEvent::from_protocol(connection, e)),
+            PresentEventData::IdleNotify(e) => PresentEvent::IdleNotify(IdleNotifyEvent::from_protocol(connection, e)),
+        })
+    }
+
+    pub(crate) fn to_protocol(self) -> PresentEventData {
+        match self {
+            PresentEvent::ConfigureNotify(e) => PresentEventData::ConfigureNotify(e.to_protocol()),
+            PresentEvent::CompleteNotify(e) => PresentEventData::CompleteNotify(e.to_protocol()),
+            PresentEvent::IdleNotify(e) => PresentEventData::IdleNotify(e.to_protocol()),
+        }
+    }
+}
+
+fn selection(connection: &X11Connection, event_id: u32, window: u32) -> PresentEventSelection<'_> {
+    PresentEventSelection {
+        handle: event_id,
+        window: Window {
+            handle: window,
+            connection,
+        },
+        connection,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PresentConfigureNotifyEvent<'a> {
+    pub selection: PresentEventSelection<'a>,
+    pub window: Window<'a>,
+    pub x: i16,
+    pub y: i16,
+    pub width: u16,
+    pub height: u16,
+    pub off_x: i16,
+    pub off_y: i16,
+    pub pixmap_width: u16,
+    pub pixmap_height: u16,
+    pub pixmap_flags: u32,
+}
+
+impl<'a> PresentConfigureNotifyEvent<'a> {
+    fn from_protocol(connection: &'a X11Connection, event: present::ConfigureNotifyEvent) -> PresentConfigureNotifyEvent<'a> {
+        Self {
+            selection: selection(connection, event.event_id, event.window),
+            window: Window {
+                handle: event.window,
+                connection,
+            },
+            x: event.x,
+            y: event.y,
+            width: event.width,
+            height: event.height,
+            off_x: event.off_x,
+            off_y: event.off_y,
+            pixmap_width: event.pixmap_width,
+            pixmap_height: event.pixmap_height,
+            pixmap_flags: event.pixmap_flags,
+        }
+    }
+
+    fn to_protocol(self) -> present::ConfigureNotifyEvent {
+        present::ConfigureNotifyEvent {
+            event_id: self.selection.handle,
+            window: self.window.handle,
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            height: self.height,
+            off_x: self.off_x,
+            off_y: self.off_y,
+            pixmap_width: self.pixmap_width,
+            pixmap_height: self.pixmap_height,
+            pixmap_flags: self.pixmap_flags,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CompleteNotifyEvent<'a> {
+    pub kind: CompleteKind,
+    pub mode: CompleteMode,
+    pub selection: PresentEventSelection<'a>,
+    pub window: Window<'a>,
+    pub serial: u32,
+    pub ust: u64,
+    pub msc: u64,
+}
+
+impl<'a> CompleteNotifyEvent<'a> {
+    fn from_protocol(connection: &'a X11Connection, event: present::CompleteNotifyEvent) -> CompleteNotifyEvent<'a> {
+        Self {
+            kind: event.kind,
+            mode: event.mode,
+            selection: selection(connection, event.event_id, event.window),
+            window: Window {
+                handle: event.window,
+                connection,
+            },
+            serial: event.serial,
+            ust: event.ust,
+            msc: event.msc,
+        }
+    }
+
+    fn to_protocol(self) -> present::CompleteNotifyEvent {
+        present::CompleteNotifyEvent {
+            kind: self.kind,
+            mode: self.mode,
+            event_id: self.selection.handle,
+            window: self.window.handle,
+            serial: self.serial,
+            ust: self.ust,
+            msc: self.msc,
+        }
+    }
+}
+
+/// The pixmap of a presentation is no longer used by the server and can be reused
+#[derive(Debug, Clone)]
+pub struct IdleNotifyEvent<'a> {
+    pub selection: PresentEventSelection<'a>,
+    pub window: Window<'a>,
+    pub serial: u32,
+    pub pixmap: Pixmap<'a>,
+    pub idle_fence: Option<Fence<'a>>,
+}
+
+impl<'a> IdleNotifyEvent<'a> {
+    fn from_protocol(connection: &'a X11Connection, event: present::IdleNotifyEvent) -> IdleNotifyEvent<'a> {
+        Self {
+            selection: selection(connection, event.event_id, event.window),
+            window: Window {
+                handle: event.window,
+                connection,
+            },
+            serial: event.serial,
+            pixmap: Pixmap {
+                handle: event.pixmap,
+                connection,
+            },
+            idle_fence: match event.idle_fence {
+                0 => None,
+                handle => Some(Fence {
+                    handle,
+                    connection,
+                }),
+            },
+        }
+    }
+
+    fn to_protocol(self) -> present::IdleNotifyEvent {
+        present::IdleNotifyEvent {
+            event_id: self.selection.handle,
+            window: self.window.handle,
+            serial: self.serial,
+            pixmap: self.pixmap.handle,
+            idle_fence: self.idle_fence.map(|x| x.handle).unwrap_or(0),
+        }
+    }
+}
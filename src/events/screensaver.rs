@@ -0,0 +1,78 @@
+pub use crate::coding::screensaver::ScreenSaverEventMask;
+use crate::{
+    coding::screensaver::{self, ScreenSaverEventCode, ScreenSaverEventData, ScreenSaverKind, ScreenSaverState},
+    net::X11Connection,
+    requests::{Timestamp, Window},
+};
+use anyhow::Result;
+
+#[derive(Clone, Debug)]
+pub enum ScreenSaverEvent<'a> {
+    Notify(ScreenSaverNotifyEvent<'a>),
+}
+
+impl<'a> ScreenSaverEvent<'a> {
+    pub(crate) fn code(&self) -> ScreenSaverEventCode {
+        match self {
+            ScreenSaverEvent::Notify(_) => ScreenSaverEventCode::Notify,
+        }
+    }
+
+    pub(crate) async fn from_protocol(connection: &'a X11Connection, from: Vec<u8>, code: u8) -> Result<ScreenSaverEvent<'a>> {
+        let event = ScreenSaverEventData::decode_sync(&mut &from[..], ScreenSaverEventCode::from_repr(code)?)?;
+        Ok(match event {
+            ScreenSaverEventData::Notify(e) => ScreenSaverEvent::Notify(ScreenSaverNotifyEvent::from_protocol(connection, e)),
+        })
+    }
+
+    pub(crate) fn to_protocol(self) -> ScreenSaverEventData {
+        match self {
+            ScreenSaverEvent::Notify(e) => ScreenSaverEventData::Notify(e.to_protocol()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScreenSaverNotifyEvent<'a> {
+    pub state: ScreenSaverState,
+    pub sequence_number: u16,
+    pub time: Timestamp,
+    pub root: Window<'a>,
+    /// the screen saver window
+    pub window: Window<'a>,
+    pub kind: ScreenSaverKind,
+    /// activated or deactivated by [`X11Connection::force_screensaver`] rather than a timeout or user input
+    pub forced: bool,
+}
+
+impl<'a> ScreenSaverNotifyEvent<'a> {
+    fn from_protocol(connection: &'a X11Connection, event: screensaver::NotifyEvent) -> ScreenSaverNotifyEvent<'a> {
+        Self {
+            state: event.state,
+            sequence_number: event.sequence_number,
+            time: Timestamp(event.time),
+            root: Window {
+                handle: event.root,
+                connection,
+            },
+            window: Window {
+                handle: event.window,
+                connection,
+            },
+            kind: event.kind,
+            forced: event.forced,
+        }
+    }
+
+    fn to_protocol(self) -> screensaver::NotifyEvent {
+        screensaver::NotifyEvent {
+            state: self.state,
+            sequence_number: self.sequence_number,
+            time: self.time.0,
+            root: self.root.handle,
+            window: self.window.handle,
+            kind: self.kind,
+            forced: self.forced,
+        }
+    }
+}
@@ -0,0 +1,116 @@
+pub use crate::coding::sync::SyncEventMask;
+use crate::{
+    coding::sync::{self, AlarmState, SyncEventCode, SyncEventData},
+    net::X11Connection,
+    requests::{Alarm, Counter, Timestamp},
+};
+use anyhow::Result;
+
+#[derive(Clone, Debug)]
+pub enum SyncEvent<'a> {
+    CounterNotify(CounterNotifyEvent<'a>),
+    AlarmNotify(AlarmNotifyEvent<'a>),
+}
+
+impl<'a> SyncEvent<'a> {
+    pub(crate) fn code(&self) -> SyncEventCode {
+        match self {
+            SyncEvent::CounterNotify(_) => SyncEventCode::CounterNotify,
+            SyncEvent::AlarmNotify(_) => SyncEventCode::AlarmNotify,
+        }
+    }
+
+    pub(crate) async fn from_protocol(connection: &'a X11Connection, from: Vec<u8>, code: u8) -> Result<SyncEvent<'a>> {
+        let event = SyncEventData::decode_sync(&mut &from[..], SyncEventCode::from_repr(code)?)?;
+        Ok(match event {
+            SyncEventData::CounterNotify(e) => SyncEvent::CounterNotify(CounterNotifyEvent::from_protocol(connection, e)),
+            SyncEventData::AlarmNotify(e) => SyncEvent::AlarmNotify(AlarmNotifyEvent::from_protocol(connection, e)),
+        })
+    }
+
+    pub(crate) fn to_protocol(self) -> SyncEventData {
+        match self {
+            SyncEvent::CounterNotify(e) => SyncEventData::CounterNotify(e.to_protocol()),
+            SyncEvent::AlarmNotify(e) => SyncEventData::AlarmNotify(e.to_protocol()),
+        }
+    }
+}
+
+/// Sent when an await finishes with a counter past its wait value by the event threshold, or the counter is destroyed
+#[derive(Debug, Clone)]
+pub struct CounterNotifyEvent<'a> {
+    pub sequence_number: u16,
+    pub counter: Counter<'a>,
+    pub wait_value: i64,
+    pub counter_value: i64,
+    pub time: Timestamp,
+    /// number of events following for the same await
+    pub count: u16,
+    pub destroyed: bool,
+}
+
+impl<'a> CounterNotifyEvent<'a> {
+    fn from_protocol(connection: &'a X11Connection, event: sync::CounterNotifyEvent) -> CounterNotifyEvent<'a> {
+        Self {
+            sequence_number: event.sequence_number,
+            counter: Counter {
+                handle: event.counter,
+                connection,
+            },
+            wait_value: event.wait_value,
+            counter_value: event.counter_value,
+            time: Timestamp(event.time),
+            count: event.count,
+            destroyed: event.destroyed,
+        }
+    }
+
+    fn to_protocol(self) -> sync::CounterNotifyEvent {
+        sync::CounterNotifyEvent {
+            sequence_number: self.sequence_number,
+            counter: self.counter.handle,
+            wait_value: self.wait_value,
+            counter_value: self.counter_value,
+            time: self.time.0,
+            count: self.count,
+            destroyed: self.destroyed,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AlarmNotifyEvent<'a> {
+    pub sequence_number: u16,
+    pub alarm: Alarm<'a>,
+    pub counter_value: i64,
+    pub alarm_value: i64,
+    pub time: Timestamp,
+    pub state: AlarmState,
+}
+
+impl<'a> AlarmNotifyEvent<'a> {
+    fn from_protocol(connection: &'a X11Connection, event: sync::AlarmNotifyEvent) -> AlarmNotifyEvent<'a> {
+        Self {
+            sequence_number: event.sequence_number,
+            alarm: Alarm {
+                handle: event.alarm,
+                connection,
+            },
+            counter_value: event.counter_value,
+            alarm_value: event.alarm_value,
+            time: Timestamp(event.time),
+            state: event.state,
+        }
+    }
+
+    fn to_protocol(self) -> sync::AlarmNotifyEvent {
+        sync::AlarmNotifyEvent {
+            sequence_number: self.sequence_number,
+            alarm: self.alarm.handle,
+            counter_value: self.counter_value,
+            alarm_value: self.alarm_value,
+            time: self.time.0,
+            state: self.state,
+        }
+    }
+}
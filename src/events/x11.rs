@@ -12,7 +12,7 @@ pub use crate::coding::x11::{
     NotifyFlags, NotifyMode, PropertyNotifyState, StackMode, VisibilityState,
 };
 
-use super::{ShapeEvent, XFEvent, XREvent};
+use super::{DRI2Event, DamageEvent, PresentEvent, ScreenSaverEvent, ShapeEvent, SyncEvent, XFEvent, XI1Event, XREvent};
 
 #[derive(Clone, Debug)]
 pub enum Event<'a> {
@@ -53,8 +53,14 @@ pub enum Event<'a> {
     XF(XFEvent<'a>),
     XR(XREvent<'a>),
     Shape(ShapeEvent<'a>),
+    DRI2(DRI2Event<'a>),
+    Damage(DamageEvent<'a>),
+    Sync(SyncEvent<'a>),
+    ScreenSaver(ScreenSaverEvent<'a>),
+    XI1(XI1Event<'a>),
     // generic event
     XI(XIEvent<'a>),
+    Present(PresentEvent<'a>),
     UnknownCore(u8, Vec<u8>),
 }
 
@@ -115,13 +121,48 @@ impl<'a> Event<'a> {
                         .ok_or_else(|| anyhow!("missing shape extension while sending event"))?
                         .event_start
             }
+            Event::DRI2(e) => {
+                e.code() as u8
+                    + connection
+                        .get_ext_info(DRI2_EXT_NAME)
+                        .ok_or_else(|| anyhow!("missing dri2 extension while sending event"))?
+                        .event_start
+            }
+            Event::Damage(e) => {
+                e.code() as u8
+                    + connection
+                        .get_ext_info(DAMAGE_EXT_NAME)
+                        .ok_or_else(|| anyhow!("missing damage extension while sending event"))?
+                        .event_start
+            }
+            Event::Sync(e) => {
+                e.code() as u8
+                    + connection
+                        .get_ext_info(SYNC_EXT_NAME)
+                        .ok_or_else(|| anyhow!("missing sync extension while sending event"))?
+                        .event_start
+            }
+            Event::ScreenSaver(e) => {
+                e.code() as u8
+                    + connection
+                        .get_ext_info(SCREENSAVER_EXT_NAME)
+                        .ok_or_else(|| anyhow!("missing mit-screen-saver extension while sending event"))?
+                        .event_start
+            }
+            Event::XI1(e) => {
+                e.code() as u8
+                    + connection
+                        .get_ext_info(XINPUT_EXT_NAME)
+                        .ok_or_else(|| anyhow!("missing xinput extension while sending event"))?
+                        .event_start
+            }
             Event::XKB(_) => {
                 connection
                     .get_ext_info(XKB_EXT_NAME)
                     .ok_or_else(|| anyhow!("missing xkb extension while sending event"))?
                     .event_start
             }
-            Event::XI(_) => EventCode::Generic as u8,
+            Event::XI(_) | Event::Present(_) => EventCode::Generic as u8,
             Event::UnknownCore(code, _) => *code,
         })
     }
@@ -170,6 +211,9 @@ impl<'a> Event<'a> {
                     crate::requests::XINPUT_EXT_NAME => {
                         return Ok(Event::XI(XIEvent::from_protocol(connection, e.evtype, e.data).await?));
                     }
+                    crate::requests::PRESENT_EXT_NAME => {
+                        return Ok(Event::Present(PresentEvent::from_protocol(connection, e.evtype, e.data).await?));
+                    }
                     _ => bail!("unimplemented event for extension {}", extension.key()),
                 }
             }
@@ -191,6 +235,21 @@ impl<'a> Event<'a> {
                     crate::requests::SHAPE_EXT_NAME => {
                         return Ok(Event::Shape(ShapeEvent::from_protocol(connection, e, code - extension.event_start).await?));
                     }
+                    crate::requests::DRI2_EXT_NAME => {
+                        return Ok(Event::DRI2(DRI2Event::from_protocol(connection, e, code - extension.event_start).await?));
+                    }
+                    crate::requests::DAMAGE_EXT_NAME => {
+                        return Ok(Event::Damage(DamageEvent::from_protocol(connection, e, code - extension.event_start).await?));
+                    }
+                    crate::requests::SYNC_EXT_NAME => {
+                        return Ok(Event::Sync(SyncEvent::from_protocol(connection, e, code - extension.event_start).await?));
+                    }
+                    crate::requests::SCREENSAVER_EXT_NAME => {
+                        return Ok(Event::ScreenSaver(ScreenSaverEvent::from_protocol(connection, e, code - extension.event_start).await?));
+                    }
+                    crate::requests::XINPUT_EXT_NAME => {
+                        return Ok(Event::XI1(XI1Event::from_protocol(connection, e, code - extension.event_start).await?));
+                    }
                     _ => bail!("unimplemented event for extension {}", extension.key()),
                 }
             }
@@ -261,6 +320,41 @@ impl<'a> Event<'a> {
                 event.encode_sync(&mut data_raw, code)?;
                 Ext(data_raw)
             }
+            Event::DRI2(e) => {
+                let code = e.code();
+                let event = e.to_protocol();
+                let mut data_raw = vec![];
+                event.encode_sync(&mut data_raw, code)?;
+                Ext(data_raw)
+            }
+            Event::Damage(e) => {
+                let code = e.code();
+                let event = e.to_protocol();
+                let mut data_raw = vec![];
+                event.encode_sync(&mut data_raw, code)?;
+                Ext(data_raw)
+            }
+            Event::Sync(e) => {
+                let code = e.code();
+                let event = e.to_protocol();
+                let mut data_raw = vec![];
+                event.encode_sync(&mut data_raw, code)?;
+                Ext(data_raw)
+            }
+            Event::ScreenSaver(e) => {
+                let code = e.code();
+                let event = e.to_protocol();
+                let mut data_raw = vec![];
+                event.encode_sync(&mut data_raw, code)?;
+                Ext(data_raw)
+            }
+            Event::XI1(e) => {
+                let code = e.code();
+                let event = e.to_protocol();
+                let mut data_raw = vec![];
+                event.encode_sync(&mut data_raw, code)?;
+                Ext(data_raw)
+            }
             Event::XI(e) => {
                 let event = e.to_protocol();
                 let mut data_raw = vec![];
@@ -277,6 +371,23 @@ impl<'a> Event<'a> {
                     data: data_raw,
                 })
             }
+            Event::Present(e) => {
+                let code = e.code();
+                let event = e.to_protocol();
+                let mut data_raw = vec![];
+                event.encode_sync(&mut data_raw, code)?;
+
+                Generic(GenericEvent {
+                    extension_opcode: connection
+                        .get_ext_info(PRESENT_EXT_NAME)
+                        .ok_or_else(|| anyhow!("missing present extension when sending event"))?
+                        .major_opcode,
+                    sequence_number: 0,
+                    length: 0,
+                    evtype: code as u16,
+                    data: data_raw,
+                })
+            }
             Event::UnknownCore(_, e) => UnknownCore(e.into()),
         };
         Ok((code, event))
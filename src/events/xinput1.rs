@@ -0,0 +1,484 @@
+pub use crate::coding::xinput1::{ChangeDevice, ClassesReportedMask, DeviceChange, XI1EventMask};
+use crate::{
+    coding::{
+        x11::{FocusDetail, FocusMode, Keybutmask, MappingNotifyRequest},
+        xinput1::{self, Property, XI1EventCode, XI1EventData},
+    },
+    net::X11Connection,
+    requests::{Atom, Device, Timestamp, Window},
+};
+use anyhow::Result;
+use bitvec::{order::Lsb0, prelude::BitVec};
+
+#[derive(Clone, Debug)]
+pub enum XI1Event<'a> {
+    DeviceValuator(XI1ValuatorEvent<'a>),
+    DeviceKeyPress(XI1InputEvent<'a>),
+    DeviceKeyRelease(XI1InputEvent<'a>),
+    DeviceButtonPress(XI1InputEvent<'a>),
+    DeviceButtonRelease(XI1InputEvent<'a>),
+    DeviceMotionNotify(XI1InputEvent<'a>),
+    DeviceFocusIn(XI1FocusEvent<'a>),
+    DeviceFocusOut(XI1FocusEvent<'a>),
+    ProximityIn(XI1InputEvent<'a>),
+    ProximityOut(XI1InputEvent<'a>),
+    DeviceStateNotify(XI1StateNotifyEvent<'a>),
+    DeviceMappingNotify(XI1MappingNotifyEvent<'a>),
+    ChangeDeviceNotify(XI1ChangeDeviceNotifyEvent<'a>),
+    DeviceKeyStateNotify(XI1StateContinuedEvent<'a>),
+    DeviceButtonStateNotify(XI1StateContinuedEvent<'a>),
+    DevicePresenceNotify(XI1PresenceNotifyEvent<'a>),
+    DevicePropertyNotify(XI1PropertyNotifyEvent<'a>),
+}
+
+impl<'a> XI1Event<'a> {
+    pub(crate) fn code(&self) -> XI1EventCode {
+        match self {
+            XI1Event::DeviceValuator(_) => XI1EventCode::DeviceValuator,
+            XI1Event::DeviceKeyPress(_) => XI1EventCode::DeviceKeyPress,
+            XI1Event::DeviceKeyRelease(_) => XI1EventCode::DeviceKeyRelease,
+            XI1Event::DeviceButtonPress(_) => XI1EventCode::DeviceButtonPress,
+            XI1Event::DeviceButtonRelease(_) => XI1EventCode::DeviceButtonRelease,
+            XI1Event::DeviceMotionNotify(_) => XI1EventCode::DeviceMotionNotify,
+            XI1Event::DeviceFocusIn(_) => XI1EventCode::DeviceFocusIn,
+            XI1Event::DeviceFocusOut(_) => XI1EventCode::DeviceFocusOut,
+            XI1Event::ProximityIn(_) => XI1EventCode::ProximityIn,
+            XI1Event::ProximityOut(_) => XI1EventCode::ProximityOut,
+            XI1Event::DeviceStateNotify(_) => XI1EventCode::DeviceStateNotify,
+            XI1Event::DeviceMappingNotify(_) => XI1EventCode::DeviceMappingNotify,
+            XI1Event::ChangeDeviceNotify(_) => XI1EventCode::ChangeDeviceNotify,
+            XI1Event::DeviceKeyStateNotify(_) => XI1EventCode::DeviceKeyStateNotify,
+            XI1Event::DeviceButtonStateNotify(_) => XI1EventCode::DeviceButtonStateNotify,
+            XI1Event::DevicePresenceNotify(_) => XI1EventCode::DevicePresenceNotify,
+            XI1Event::DevicePropertyNotify(_) => XI1EventCode::DevicePropertyNotify,
+        }
+    }
+
+    pub(crate) async fn from_protocol(connection: &'a X11Connection, from: Vec<u8>, code: u8) -> Result<XI1Event<'a>> {
+        let event = XI1EventData::decode_sync(&mut &from[..], XI1EventCode::from_repr(code)?)?;
+        Ok(match event {
+            XI1EventData::DeviceValuator(e) => XI1Event::DeviceValuator(XI1ValuatorEvent::from_protocol(connection, e)),
+            XI1EventData::DeviceKeyPress(e) => XI1Event::DeviceKeyPress(XI1InputEvent::from_protocol(connection, e)),
+            XI1EventData::DeviceKeyRelease(e) => XI1Event::DeviceKeyRelease(XI1InputEvent::from_protocol(connection, e)),
+            XI1EventData::DeviceButtonPress(e) => XI1Event::DeviceButtonPress(XI1InputEvent::from_protocol(connection, e)),
+            XI1EventData::DeviceButtonRelease(e) => XI1Event::DeviceButtonRelease(XI1InputEvent::from_protocol(connection, e)),
+            XI1EventData::DeviceMotionNotify(e) => XI1Event::DeviceMotionNotify(XI1InputEvent::from_protocol(connection, e)),
+            XI1EventData::DeviceFocusIn(e) => XI1Event::DeviceFocusIn(XI1FocusEvent::from_protocol(connection, e)?),
+            XI1EventData::DeviceFocusOut(e) => XI1Event::DeviceFocusOut(XI1FocusEvent::from_protocol(connection, e)?),
+            XI1EventData::ProximityIn(e) => XI1Event::ProximityIn(XI1InputEvent::from_protocol(connection, e)),
+            XI1EventData::ProximityOut(e) => XI1Event::ProximityOut(XI1InputEvent::from_protocol(connection, e)),
+            XI1EventData::DeviceStateNotify(e) => XI1Event::DeviceStateNotify(XI1StateNotifyEvent::from_protocol(connection, e)),
+            XI1EventData::DeviceMappingNotify(e) => XI1Event::DeviceMappingNotify(XI1MappingNotifyEvent::from_protocol(connection, e)?),
+            XI1EventData::ChangeDeviceNotify(e) => XI1Event::ChangeDeviceNotify(XI1ChangeDeviceNotifyEvent::from_protocol(connection, e)),
+            XI1EventData::DeviceKeyStateNotify(e) => {
+                XI1Event::DeviceKeyStateNotify(XI1StateContinuedEvent::from_protocol(connection, e.device_id, e.sequence_number, e.keys))
+            }
+            XI1EventData::DeviceButtonStateNotify(e) => {
+                XI1Event::DeviceButtonStateNotify(XI1StateContinuedEvent::from_protocol(connection, e.device_id, e.sequence_number, e.buttons))
+            }
+            XI1EventData::DevicePresenceNotify(e) => XI1Event::DevicePresenceNotify(XI1PresenceNotifyEvent::from_protocol(connection, e)),
+            XI1EventData::DevicePropertyNotify(e) => XI1Event::DevicePropertyNotify(XI1PropertyNotifyEvent::from_protocol(connection, e).await?),
+        })
+    }
+
+    pub(crate) fn to_protocol(self) -> XI1EventData {
+        match self {
+            XI1Event::DeviceValuator(e) => XI1EventData::DeviceValuator(e.to_protocol()),
+            XI1Event::DeviceKeyPress(e) => XI1EventData::DeviceKeyPress(e.to_protocol()),
+            XI1Event::DeviceKeyRelease(e) => XI1EventData::DeviceKeyRelease(e.to_protocol()),
+            XI1Event::DeviceButtonPress(e) => XI1EventData::DeviceButtonPress(e.to_protocol()),
+            XI1Event::DeviceButtonRelease(e) => XI1EventData::DeviceButtonRelease(e.to_protocol()),
+            XI1Event::DeviceMotionNotify(e) => XI1EventData::DeviceMotionNotify(e.to_protocol()),
+            XI1Event::DeviceFocusIn(e) => XI1EventData::DeviceFocusIn(e.to_protocol()),
+            XI1Event::DeviceFocusOut(e) => XI1EventData::DeviceFocusOut(e.to_protocol()),
+            XI1Event::ProximityIn(e) => XI1EventData::ProximityIn(e.to_protocol()),
+            XI1Event::ProximityOut(e) => XI1EventData::ProximityOut(e.to_protocol()),
+            XI1Event::DeviceStateNotify(e) => XI1EventData::DeviceStateNotify(e.to_protocol()),
+            XI1Event::DeviceMappingNotify(e) => XI1EventData::DeviceMappingNotify(e.to_protocol()),
+            XI1Event::ChangeDeviceNotify(e) => XI1EventData::ChangeDeviceNotify(e.to_protocol()),
+            XI1Event::DeviceKeyStateNotify(e) => {
+                let (device_id, sequence_number, keys) = e.to_protocol();
+                XI1EventData::DeviceKeyStateNotify(xinput1::DeviceKeyStateNotifyEvent {
+                    device_id,
+                    sequence_number,
+                    keys,
+                })
+            }
+            XI1Event::DeviceButtonStateNotify(e) => {
+                let (device_id, sequence_number, buttons) = e.to_protocol();
+                XI1EventData::DeviceButtonStateNotify(xinput1::DeviceButtonStateNotifyEvent {
+                    device_id,
+                    sequence_number,
+                    buttons,
+                })
+            }
+            XI1Event::DevicePresenceNotify(e) => XI1EventData::DevicePresenceNotify(e.to_protocol()),
+            XI1Event::DevicePropertyNotify(e) => XI1EventData::DevicePropertyNotify(e.to_protocol()),
+        }
+    }
+}
+
+// the high bit of device ids in some events is set if more events for the device follow
+fn decode_device_id(connection: &X11Connection, device_id: u8) -> (Device<'_>, bool) {
+    (Device::from_xi1_id(connection, device_id & 0x7f), device_id & 0x80 != 0)
+}
+
+fn encode_device_id(device: Device<'_>, more: bool) -> u8 {
+    device.raw_id() as u8 | if more { 0x80 } else { 0 }
+}
+
+#[derive(Debug, Clone)]
+pub struct XI1ValuatorEvent<'a> {
+    pub device: Device<'a>,
+    /// more valuator events for the same device event follow
+    pub more: bool,
+    pub sequence_number: u16,
+    pub state: Keybutmask,
+    pub first_valuator: u8,
+    /// up to 6 valuators, starting at `first_valuator`
+    pub valuators: Vec<i32>,
+}
+
+impl<'a> XI1ValuatorEvent<'a> {
+    fn from_protocol(connection: &'a X11Connection, event: xinput1::DeviceValuatorEvent) -> Self {
+        let (device, more) = decode_device_id(connection, event.device_id);
+        let mut valuators = event.valuators;
+        valuators.truncate(event.num_valuators as usize);
+        Self {
+            device,
+            more,
+            sequence_number: event.sequence_number,
+            state: Keybutmask(event.device_state),
+            first_valuator: event.first_valuator,
+            valuators,
+        }
+    }
+
+    fn to_protocol(self) -> xinput1::DeviceValuatorEvent {
+        let num_valuators = self.valuators.len() as u8;
+        let mut valuators = self.valuators;
+        valuators.resize(6, 0);
+        xinput1::DeviceValuatorEvent {
+            device_id: encode_device_id(self.device, self.more),
+            sequence_number: self.sequence_number,
+            device_state: self.state.0,
+            num_valuators,
+            first_valuator: self.first_valuator,
+            valuators,
+        }
+    }
+}
+
+/// A key, button, motion or proximity event from a device
+#[derive(Debug, Clone)]
+pub struct XI1InputEvent<'a> {
+    pub device: Device<'a>,
+    /// valuator events for this event follow
+    pub more: bool,
+    pub sequence_number: u16,
+    /// keycode or button, 0 for motion and proximity events
+    pub detail: u8,
+    pub time: Timestamp,
+    pub root_window: Window<'a>,
+    pub event_window: Window<'a>,
+    pub child_window: Option<Window<'a>>,
+    pub root_x: i16,
+    pub root_y: i16,
+    pub event_x: i16,
+    pub event_y: i16,
+    pub state: Keybutmask,
+    pub same_screen: bool,
+}
+
+impl<'a> XI1InputEvent<'a> {
+    fn from_protocol(connection: &'a X11Connection, event: xinput1::DeviceKeyEvent) -> Self {
+        let (device, more) = decode_device_id(connection, event.device_id);
+        Self {
+            device,
+            more,
+            sequence_number: event.sequence_number,
+            detail: event.detail,
+            time: Timestamp(event.time),
+            root_window: Window {
+                handle: event.root_window,
+                connection,
+            },
+            event_window: Window {
+                handle: event.event_window,
+                connection,
+            },
+            child_window: match event.child_window {
+                0 => None,
+                handle => Some(Window {
+                    handle,
+                    connection,
+                }),
+            },
+            root_x: event.root_x,
+            root_y: event.root_y,
+            event_x: event.event_x,
+            event_y: event.event_y,
+            state: Keybutmask(event.state.0),
+            same_screen: event.same_screen,
+        }
+    }
+
+    fn to_protocol(self) -> xinput1::DeviceKeyEvent {
+        xinput1::DeviceKeyEvent {
+            detail: self.detail,
+            sequence_number: self.sequence_number,
+            time: self.time.0,
+            root_window: self.root_window.handle,
+            event_window: self.event_window.handle,
+            child_window: self.child_window.map(|x| x.handle).unwrap_or(0),
+            root_x: self.root_x,
+            root_y: self.root_y,
+            event_x: self.event_x,
+            event_y: self.event_y,
+            state: xinput1::Keybutmask(self.state.0),
+            same_screen: self.same_screen,
+            device_id: encode_device_id(self.device, self.more),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct XI1FocusEvent<'a> {
+    pub device: Device<'a>,
+    pub sequence_number: u16,
+    pub detail: FocusDetail,
+    pub time: Timestamp,
+    pub window: Window<'a>,
+    pub mode: FocusMode,
+}
+
+impl<'a> XI1FocusEvent<'a> {
+    fn from_protocol(connection: &'a X11Connection, event: xinput1::DeviceFocusEvent) -> Result<Self> {
+        Ok(Self {
+            device: Device::from_xi1_id(connection, event.device_id),
+            sequence_number: event.sequence_number,
+            detail: FocusDetail::from_repr(event.detail)?,
+            time: Timestamp(event.time),
+            window: Window {
+                handle: event.window,
+                connection,
+            },
+            mode: FocusMode::from_repr(event.mode)?,
+        })
+    }
+
+    fn to_protocol(self) -> xinput1::DeviceFocusEvent {
+        xinput1::DeviceFocusEvent {
+            detail: self.detail as u8,
+            sequence_number: self.sequence_number,
+            time: self.time.0,
+            window: self.window.handle,
+            mode: self.mode as u8,
+            device_id: self.device.raw_id() as u8,
+        }
+    }
+}
+
+/// State of a device, followed by [`XI1Event::DeviceKeyStateNotify`] and [`XI1Event::DeviceButtonStateNotify`] if `more` is set
+#[derive(Debug, Clone)]
+pub struct XI1StateNotifyEvent<'a> {
+    pub device: Device<'a>,
+    pub more: bool,
+    pub sequence_number: u16,
+    pub time: Timestamp,
+    pub num_keys: u8,
+    pub num_buttons: u8,
+    pub num_valuators: u8,
+    pub classes_reported: ClassesReportedMask,
+    /// state of the first 32 buttons
+    pub buttons: BitVec<u8, Lsb0>,
+    /// state of the first 32 keys
+    pub keys: BitVec<u8, Lsb0>,
+    /// values of the first 3 valuators
+    pub valuators: Vec<u32>,
+}
+
+impl<'a> XI1StateNotifyEvent<'a> {
+    fn from_protocol(connection: &'a X11Connection, event: xinput1::DeviceStateNotifyEvent) -> Self {
+        let (device, more) = decode_device_id(connection, event.device_id);
+        Self {
+            device,
+            more,
+            sequence_number: event.sequence_number,
+            time: Timestamp(event.time),
+            num_keys: event.num_keys,
+            num_buttons: event.num_buttons,
+            num_valuators: event.num_valuators,
+            classes_reported: event.classes_reported,
+            buttons: BitVec::from_vec(event.buttons),
+            keys: BitVec::from_vec(event.keys),
+            valuators: event.valuators,
+        }
+    }
+
+    fn to_protocol(self) -> xinput1::DeviceStateNotifyEvent {
+        xinput1::DeviceStateNotifyEvent {
+            device_id: encode_device_id(self.device, self.more),
+            sequence_number: self.sequence_number,
+            time: self.time.0,
+            num_keys: self.num_keys,
+            num_buttons: self.num_buttons,
+            num_valuators: self.num_valuators,
+            classes_reported: self.classes_reported,
+            buttons: self.buttons.into_vec(),
+            keys: self.keys.into_vec(),
+            valuators: self.valuators,
+        }
+    }
+}
+
+/// Key or button state continuing a [`XI1StateNotifyEvent`]
+#[derive(Debug, Clone)]
+pub struct XI1StateContinuedEvent<'a> {
+    pub device: Device<'a>,
+    pub more: bool,
+    pub sequence_number: u16,
+    /// state of the keys or buttons from 32 on
+    pub state: BitVec<u8, Lsb0>,
+}
+
+impl<'a> XI1StateContinuedEvent<'a> {
+    fn from_protocol(connection: &'a X11Connection, device_id: u8, sequence_number: u16, state: Vec<u8>) -> Self {
+        let (device, more) = decode_device_id(connection, device_id);
+        Self {
+            device,
+            more,
+            sequence_number,
+            state: BitVec::from_vec(state),
+        }
+    }
+
+    fn to_protocol(self) -> (u8, u16, Vec<u8>) {
+        (encode_device_id(self.device, self.more), self.sequence_number, self.state.into_vec())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct XI1MappingNotifyEvent<'a> {
+    pub device: Device<'a>,
+    pub sequence_number: u16,
+    pub request: MappingNotifyRequest,
+    pub first_keycode: u8,
+    pub count: u8,
+    pub time: Timestamp,
+}
+
+impl<'a> XI1MappingNotifyEvent<'a> {
+    fn from_protocol(connection: &'a X11Connection, event: xinput1::DeviceMappingNotifyEvent) -> Result<Self> {
+        Ok(Self {
+            device: Device::from_xi1_id(connection, event.device_id),
+            sequence_number: event.sequence_number,
+            request: MappingNotifyRequest::from_repr(event.request)?,
+            first_keycode: event.first_keycode,
+            count: event.count,
+            time: Timestamp(event.time),
+        })
+    }
+
+    fn to_protocol(self) -> xinput1::DeviceMappingNotifyEvent {
+        xinput1::DeviceMappingNotifyEvent {
+            device_id: self.device.raw_id() as u8,
+            sequence_number: self.sequence_number,
+            request: self.request as u8,
+            first_keycode: self.first_keycode,
+            count: self.count,
+            time: self.time.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct XI1ChangeDeviceNotifyEvent<'a> {
+    pub device: Device<'a>,
+    pub sequence_number: u16,
+    pub time: Timestamp,
+    pub request: ChangeDevice,
+}
+
+impl<'a> XI1ChangeDeviceNotifyEvent<'a> {
+    fn from_protocol(connection: &'a X11Connection, event: xinput1::ChangeDeviceNotifyEvent) -> Self {
+        Self {
+            device: Device::from_xi1_id(connection, event.device_id),
+            sequence_number: event.sequence_number,
+            time: Timestamp(event.time),
+            request: event.request,
+        }
+    }
+
+    fn to_protocol(self) -> xinput1::ChangeDeviceNotifyEvent {
+        xinput1::ChangeDeviceNotifyEvent {
+            device_id: self.device.raw_id() as u8,
+            sequence_number: self.sequence_number,
+            time: self.time.0,
+            request: self.request,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct XI1PresenceNotifyEvent<'a> {
+    pub sequence_number: u16,
+    pub time: Timestamp,
+    pub change: DeviceChange,
+    pub device: Device<'a>,
+    /// the control that changed, for [`DeviceChange::ControlChanged`]
+    pub control: u16,
+}
+
+impl<'a> XI1PresenceNotifyEvent<'a> {
+    fn from_protocol(connection: &'a X11Connection, event: xinput1::DevicePresenceNotifyEvent) -> Self {
+        Self {
+            sequence_number: event.sequence_number,
+            time: Timestamp(event.time),
+            change: event.device_change,
+            device: Device::from_xi1_id(connection, event.device_id),
+            control: event.control,
+        }
+    }
+
+    fn to_protocol(self) -> xinput1::DevicePresenceNotifyEvent {
+        xinput1::DevicePresenceNotifyEvent {
+            sequence_number: self.sequence_number,
+            time: self.time.0,
+            device_change: self.change,
+            device_id: self.device.raw_id() as u8,
+            control: self.control,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct XI1PropertyNotifyEvent<'a> {
+    pub device: Device<'a>,
+    pub sequence_number: u16,
+    pub time: Timestamp,
+    pub property: Atom,
+    pub deleted: bool,
+}
+
+impl<'a> XI1PropertyNotifyEvent<'a> {
+    async fn from_protocol(connection: &'a X11Connection, event: xinput1::DevicePropertyNotifyEvent) -> Result<XI1PropertyNotifyEvent<'a>> {
+        Ok(Self {
+            device: Device::from_xi1_id(connection, event.device_id),
+            sequence_number: event.sequence_number,
+            time: Timestamp(event.time),
+            property: connection.get_atom_name(event.property_atom).await?,
+            deleted: event.state == Property::Delete,
+        })
+    }
+
+    fn to_protocol(self) -> xinput1::DevicePropertyNotifyEvent {
+        xinput1::DevicePropertyNotifyEvent {
+            state: if self.deleted { Property::Delete } else { Property::NewValue },
+            sequence_number: self.sequence_number,
+            time: self.time.0,
+            property_atom: self.property.handle,
+            device_id: self.device.raw_id() as u8,
+        }
+    }
+}
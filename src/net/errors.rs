@@ -1,5 +1,7 @@
-pub use crate::coding::{xfixes::XFErrorCode, xinput2::XIErrorCode, xkb::XKBErrorCode, xrecord::XRecordErrorCode, ErrorCode};
-use crate::requests::{XFIXES_EXT_NAME, XINPUT_EXT_NAME, XKB_EXT_NAME, XRECORD_EXT_NAME};
+pub use crate::coding::{
+    damage::DamageErrorCode, render::XRenderErrorCode, shm::ShmErrorCode, sync::SyncErrorCode, xfixes::XFErrorCode, xinput2::XIErrorCode, xkb::XKBErrorCode,
+    xrandr::XRErrorCode, xrecord::XRecordErrorCode, ErrorCode,
+};
 
 use super::*;
 
@@ -10,6 +12,11 @@ pub enum X11ErrorCode {
     XI(XIErrorCode),
     XF(XFErrorCode),
     XRecord(XRecordErrorCode),
+    XR(XRErrorCode),
+    Render(XRenderErrorCode),
+    Shm(ShmErrorCode),
+    Damage(DamageErrorCode),
+    Sync(SyncErrorCode),
     Unknown(u8),
 }
 
@@ -18,24 +25,27 @@ impl X11ErrorCode {
         if let Ok(code) = ErrorCode::from_repr(code) {
             return X11ErrorCode::X11(code);
         }
-        if let Some(xkb) = connection.get_ext_info(XKB_EXT_NAME) {
-            if code == xkb.error_start {
-                return X11ErrorCode::XKB(XKBErrorCode::Keyboard);
+        for entry in connection.0.registered_extensions.iter() {
+            let info = entry.value();
+            // extensions without errors report a first error of 0
+            if info.error_start == 0 || code < info.error_start {
+                continue;
             }
-        }
-        if let Some(xinput) = connection.get_ext_info(XINPUT_EXT_NAME) {
-            if code == xinput.error_start {
-                return X11ErrorCode::XI(XIErrorCode::BadDevice);
-            }
-        }
-        if let Some(xfixes) = connection.get_ext_info(XFIXES_EXT_NAME) {
-            if code == xfixes.error_start {
-                return X11ErrorCode::XF(XFErrorCode::BadRegion);
-            }
-        }
-        if let Some(xrecord) = connection.get_ext_info(XRECORD_EXT_NAME) {
-            if code == xrecord.error_start {
-                return X11ErrorCode::XRecord(XRecordErrorCode::RecordContext);
+            let offset = code - info.error_start;
+            let decoded = match info.extension {
+                Extension::XKB => XKBErrorCode::from_repr(offset).map(X11ErrorCode::XKB),
+                Extension::XInput => XIErrorCode::from_repr(offset).map(X11ErrorCode::XI),
+                Extension::XFixes => XFErrorCode::from_repr(offset).map(X11ErrorCode::XF),
+                Extension::XRecord => XRecordErrorCode::from_repr(offset).map(X11ErrorCode::XRecord),
+                Extension::XRandr => XRErrorCode::from_repr(offset).map(X11ErrorCode::XR),
+                Extension::Render => XRenderErrorCode::from_repr(offset).map(X11ErrorCode::Render),
+                Extension::Shm => ShmErrorCode::from_repr(offset).map(X11ErrorCode::Shm),
+                Extension::Damage => DamageErrorCode::from_repr(offset).map(X11ErrorCode::Damage),
+                Extension::Sync => SyncErrorCode::from_repr(offset).map(X11ErrorCode::Sync),
+                _ => continue,
+            };
+            if let Ok(decoded) = decoded {
+                return decoded;
             }
         }
         X11ErrorCode::Unknown(code)
@@ -46,16 +56,133 @@ impl X11ErrorCode {
 pub struct X11ErrorReply {
     pub bad_value: u32,
     pub code: X11ErrorCode,
+    /// full sequence number of the request that caused the error
+    pub sequence_number: u64,
+    pub major_opcode: u8,
+    pub minor_opcode: u16,
+}
+
+impl X11ErrorReply {
+    pub(crate) fn from_raw(connection: &X11Connection, sequence_number: u64, reply: &ErrorReply) -> Self {
+        X11ErrorReply {
+            bad_value: reply.bad_value,
+            code: X11ErrorCode::from_raw(connection, reply.code),
+            sequence_number,
+            major_opcode: reply.major_opcode,
+            minor_opcode: reply.minor_opcode,
+        }
+    }
 }
 
 impl fmt::Display for X11ErrorReply {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "x11 error: {:?} <{}>", self.code, self.bad_value)
+        write!(
+            f,
+            "x11 error: {:?} <{}> in request {}/{} (seq {})",
+            self.code, self.bad_value, self.major_opcode, self.minor_opcode, self.sequence_number
+        )
     }
 }
 
 impl std::error::Error for X11ErrorReply {}
 
+/// Returned (wrapped in an [`anyhow::Error`]) when a request exceeds the server's maximum request length
+#[derive(Debug, Clone, Copy)]
+pub struct RequestTooLarge {
+    pub major_opcode: u8,
+    pub minor_opcode: u8,
+    /// in 4-byte units
+    pub length: u32,
+    /// in 4-byte units
+    pub maximum_length: u32,
+}
+
+impl fmt::Display for RequestTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "request {}/{} too large: {} > {} words",
+            self.major_opcode, self.minor_opcode, self.length, self.maximum_length
+        )
+    }
+}
+
+impl std::error::Error for RequestTooLarge {}
+
+/// Returned (wrapped in an [`anyhow::Error`]) when some of the wire requests a single call was split into failed
+#[derive(Debug)]
+pub struct SplitRequestError {
+    /// number of wire requests the call was split into
+    pub total: usize,
+    /// index of each failed wire request with its error
+    pub failures: Vec<(usize, anyhow::Error)>,
+}
+
+impl fmt::Display for SplitRequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of {} split requests failed", self.failures.len(), self.total)?;
+        if let Some((index, error)) = self.failures.first() {
+            write!(f, ", first at {}: {}", index, error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SplitRequestError {}
+
+/// Why a connection was closed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CloseReason {
+    /// closed by [`X11Connection::close`]
+    Closed,
+    /// the server closed the connection
+    Eof,
+    Io(std::io::ErrorKind, String),
+    /// the server sent something we couldn't decode
+    Protocol(String),
+}
+
+impl CloseReason {
+    pub(crate) fn from_error(error: &anyhow::Error) -> Self {
+        match error.chain().find_map(|e| e.downcast_ref::<std::io::Error>()) {
+            Some(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => CloseReason::Eof,
+            Some(e) => CloseReason::Io(e.kind(), e.to_string()),
+            None => CloseReason::Protocol(format!("{:#}", error)),
+        }
+    }
+}
+
+impl fmt::Display for CloseReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CloseReason::Closed => write!(f, "connection closed"),
+            CloseReason::Eof => write!(f, "connection closed by server"),
+            CloseReason::Io(_, e) => write!(f, "io error: {}", e),
+            CloseReason::Protocol(e) => write!(f, "protocol error: {}", e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionState {
+    Open,
+    Closed(CloseReason),
+}
+
+/// Returned (wrapped in an [`anyhow::Error`]) for requests that were pending or sent after the connection closed
+#[derive(Debug, Clone)]
+pub struct ConnectionLost {
+    pub reason: CloseReason,
+}
+
+impl fmt::Display for ConnectionLost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "x11 connection lost: {}", self.reason)
+    }
+}
+
+impl std::error::Error for ConnectionLost {}
+
 pub enum X11Error {
     Error(anyhow::Error),
     X11Error(X11ErrorReply),
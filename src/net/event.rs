@@ -6,12 +6,19 @@ use super::*;
 pub use crate::coding::x11::X11EventMask;
 pub(crate) use crate::coding::Event as RawEvent;
 use crate::{
-    coding::{shape::ShapeEventMask, xfixes::XFEventMask, xinput2::XIEventMask, xkb::XKBEventMask, xrandr::XREventMask},
+    coding::{
+        damage::DamageEventMask, dri2::DRI2EventMask, present::PresentEventMask, screensaver::ScreenSaverEventMask, shape::ShapeEventMask, sync::SyncEventMask,
+        xfixes::XFEventMask, xinput1::XI1EventMask, xinput2::XIEventMask, xkb::XKBEventMask, xrandr::XREventMask,
+    },
     events::Event,
-    requests::{SHAPE_EXT_NAME, XFIXES_EXT_NAME, XINPUT_EXT_NAME, XKB_EXT_NAME, XRANDR_EXT_NAME},
+    requests::{
+        DAMAGE_EXT_NAME, DRI2_EXT_NAME, PRESENT_EXT_NAME, SCREENSAVER_EXT_NAME, SHAPE_EXT_NAME, SYNC_EXT_NAME, XFIXES_EXT_NAME, XINPUT_EXT_NAME, XKB_EXT_NAME,
+        XRANDR_EXT_NAME,
+    },
 };
 
-type RawEventData = (u8, RawEvent);
+// event code, widened sequence number and event
+pub(crate) type RawEventData = (u8, u64, RawEvent);
 
 pub struct EventReceiver<'a> {
     connection: &'a X11Connection,
@@ -27,6 +34,12 @@ pub struct EventFilter {
     pub xfixes_events: XFEventMask,
     pub xrandr_events: XREventMask,
     pub shape_events: ShapeEventMask,
+    pub dri2_events: DRI2EventMask,
+    pub damage_events: DamageEventMask,
+    pub present_events: PresentEventMask,
+    pub sync_events: SyncEventMask,
+    pub screensaver_events: ScreenSaverEventMask,
+    pub xi1_events: XI1EventMask,
 }
 
 impl From<X11EventMask> for EventFilter {
@@ -83,6 +96,60 @@ impl From<ShapeEventMask> for EventFilter {
     }
 }
 
+impl From<DRI2EventMask> for EventFilter {
+    fn from(from: DRI2EventMask) -> Self {
+        EventFilter {
+            dri2_events: from,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<DamageEventMask> for EventFilter {
+    fn from(from: DamageEventMask) -> Self {
+        EventFilter {
+            damage_events: from,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<PresentEventMask> for EventFilter {
+    fn from(from: PresentEventMask) -> Self {
+        EventFilter {
+            present_events: from,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<SyncEventMask> for EventFilter {
+    fn from(from: SyncEventMask) -> Self {
+        EventFilter {
+            sync_events: from,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<ScreenSaverEventMask> for EventFilter {
+    fn from(from: ScreenSaverEventMask) -> Self {
+        EventFilter {
+            screensaver_events: from,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<XI1EventMask> for EventFilter {
+    fn from(from: XI1EventMask) -> Self {
+        EventFilter {
+            xi1_events: from,
+            ..Default::default()
+        }
+    }
+}
+
 impl BitOr for EventFilter {
     type Output = Self;
 
@@ -94,6 +161,12 @@ impl BitOr for EventFilter {
             xfixes_events: self.xfixes_events | rhs.xfixes_events,
             xrandr_events: self.xrandr_events | rhs.xrandr_events,
             shape_events: self.shape_events | rhs.shape_events,
+            dri2_events: self.dri2_events | rhs.dri2_events,
+            damage_events: self.damage_events | rhs.damage_events,
+            present_events: self.present_events | rhs.present_events,
+            sync_events: self.sync_events | rhs.sync_events,
+            screensaver_events: self.screensaver_events | rhs.screensaver_events,
+            xi1_events: self.xi1_events | rhs.xi1_events,
         }
     }
 }
@@ -106,6 +179,12 @@ impl EventFilter {
         xfixes_events: XFEventMask::ALL,
         xrandr_events: XREventMask::ALL,
         shape_events: ShapeEventMask::ALL,
+        dri2_events: DRI2EventMask::ALL,
+        damage_events: DamageEventMask::ALL,
+        present_events: PresentEventMask::ALL,
+        sync_events: SyncEventMask::ALL,
+        screensaver_events: ScreenSaverEventMask::ALL,
+        xi1_events: XI1EventMask::ALL,
     };
     pub const ZERO: Self = Self {
         core_events: X11EventMask::ZERO,
@@ -114,6 +193,12 @@ impl EventFilter {
         xfixes_events: XFEventMask::ZERO,
         xrandr_events: XREventMask::ZERO,
         shape_events: ShapeEventMask::ZERO,
+        dri2_events: DRI2EventMask::ZERO,
+        damage_events: DamageEventMask::ZERO,
+        present_events: PresentEventMask::ZERO,
+        sync_events: SyncEventMask::ZERO,
+        screensaver_events: ScreenSaverEventMask::ZERO,
+        xi1_events: XI1EventMask::ZERO,
     };
 }
 
@@ -162,6 +247,51 @@ impl ShapeEventMask {
     }
 }
 
+impl DRI2EventMask {
+    fn matches(&self, code: u8) -> bool {
+        let bit = 1u16 << code;
+        (self.0 & bit) != 0
+    }
+}
+
+impl DamageEventMask {
+    fn matches(&self, code: u8) -> bool {
+        let bit = 1u16 << code;
+        (self.0 & bit) != 0
+    }
+}
+
+impl SyncEventMask {
+    fn matches(&self, code: u8) -> bool {
+        let bit = 1u16 << code;
+        (self.0 & bit) != 0
+    }
+}
+
+impl ScreenSaverEventMask {
+    fn matches(&self, code: u8) -> bool {
+        let bit = 1u16 << code;
+        (self.0 & bit) != 0
+    }
+}
+
+impl XI1EventMask {
+    fn matches(&self, code: u8) -> bool {
+        let bit = 1u32 << code;
+        (self.0 & bit) != 0
+    }
+}
+
+impl PresentEventMask {
+    fn matches(&self, code: u16) -> bool {
+        if code >= 32 {
+            return false;
+        }
+        let bit = 1u32 << code;
+        (self.0 & bit) != 0
+    }
+}
+
 impl<'a> EventReceiver<'a> {
     pub fn set_filter(&mut self, filter: impl Into<EventFilter>) {
         self.filter = filter.into();
@@ -169,7 +299,13 @@ impl<'a> EventReceiver<'a> {
 
     async fn recv_raw(&mut self) -> Option<RawEventData> {
         loop {
-            match self.receiver.recv().await {
+            let received = tokio::select! {
+                // drain events already received before reporting the close
+                biased;
+                received = self.receiver.recv() => received,
+                _ = self.connection.closed() => return None,
+            };
+            match received {
                 Err(RecvError::Lagged(_)) => (),
                 Ok(x) => return Some(x),
                 Err(RecvError::Closed) => return None,
@@ -178,8 +314,15 @@ impl<'a> EventReceiver<'a> {
     }
 
     pub async fn recv(&mut self) -> Option<Result<Event<'_>>> {
+        Some(self.recv_sequenced().await?.map(|(_, event)| event))
+    }
+
+    /// Like [`EventReceiver::recv`], also returning the full sequence number of the last request the server processed before the event
+    pub async fn recv_sequenced(&mut self) -> Option<Result<(u64, Event<'_>)>> {
+        let mut sequence;
         let (code, event) = loop {
-            let (code, event) = self.recv_raw().await?;
+            let (code, seq, event) = self.recv_raw().await?;
+            sequence = seq;
 
             if self.filter.core_events.matches(code) {
                 break (code, event);
@@ -228,6 +371,51 @@ impl<'a> EventReceiver<'a> {
                 }
             }
 
+            if let Some(dri2) = self.connection.get_ext_info(DRI2_EXT_NAME) {
+                if code >= dri2.event_start && code < dri2.event_start + dri2.event_count {
+                    if self.filter.dri2_events.matches(code - dri2.event_start) {
+                        break (code, event);
+                    }
+                    continue;
+                }
+            }
+
+            if let Some(damage) = self.connection.get_ext_info(DAMAGE_EXT_NAME) {
+                if code >= damage.event_start && code < damage.event_start + damage.event_count {
+                    if self.filter.damage_events.matches(code - damage.event_start) {
+                        break (code, event);
+                    }
+                    continue;
+                }
+            }
+
+            if let Some(sync) = self.connection.get_ext_info(SYNC_EXT_NAME) {
+                if code >= sync.event_start && code < sync.event_start + sync.event_count {
+                    if self.filter.sync_events.matches(code - sync.event_start) {
+                        break (code, event);
+                    }
+                    continue;
+                }
+            }
+
+            if let Some(screensaver) = self.connection.get_ext_info(SCREENSAVER_EXT_NAME) {
+                if code >= screensaver.event_start && code < screensaver.event_start + screensaver.event_count {
+                    if self.filter.screensaver_events.matches(code - screensaver.event_start) {
+                        break (code, event);
+                    }
+                    continue;
+                }
+            }
+
+            if let Some(xinput) = self.connection.get_ext_info(XINPUT_EXT_NAME) {
+                if code >= xinput.event_start && code < xinput.event_start + xinput.event_count {
+                    if self.filter.xi1_events.matches(code - xinput.event_start) {
+                        break (code, event);
+                    }
+                    continue;
+                }
+            }
+
             if let RawEvent::Generic(generic) = &event {
                 if let Some(xinput) = self.connection.get_ext_info(XINPUT_EXT_NAME) {
                     if generic.extension_opcode == xinput.major_opcode {
@@ -237,13 +425,60 @@ impl<'a> EventReceiver<'a> {
                         continue;
                     }
                 }
+                if let Some(present) = self.connection.get_ext_info(PRESENT_EXT_NAME) {
+                    if generic.extension_opcode == present.major_opcode {
+                        if self.filter.present_events.matches(generic.evtype) {
+                            break (code, event);
+                        }
+                        continue;
+                    }
+                }
             }
         };
 
-        Some(Event::from_protocol(self.connection, code, event).await)
+        Some(Event::from_protocol(self.connection, code, event).await.map(|event| (sequence, event)))
     }
 }
 
+/// The 16-bit sequence number sent with `event`, KeymapNotify events have none
+pub(crate) fn raw_sequence_number(event: &RawEvent) -> Option<u16> {
+    use RawEvent::*;
+    Some(match event {
+        KeyPress(e) | KeyRelease(e) => e.sequence_number,
+        ButtonPress(e) | ButtonRelease(e) => e.sequence_number,
+        MotionNotify(e) => e.sequence_number,
+        EnterNotify(e) | LeaveNotify(e) => e.sequence_number,
+        FocusIn(e) | FocusOut(e) => e.sequence_number,
+        KeymapNotify(_) => return None,
+        Expose(e) => e.sequence_number,
+        GraphicsExposure(e) => e.sequence_number,
+        NoExposure(e) => e.sequence_number,
+        VisibilityNotify(e) => e.sequence_number,
+        CreateNotify(e) => e.sequence_number,
+        DestroyNotify(e) => e.sequence_number,
+        UnmapNotify(e) => e.sequence_number,
+        MapNotify(e) => e.sequence_number,
+        MapRequest(e) => e.sequence_number,
+        ReparentNotify(e) => e.sequence_number,
+        ConfigureNotify(e) => e.sequence_number,
+        ConfigureRequest(e) => e.sequence_number,
+        GravityNotify(e) => e.sequence_number,
+        ResizeRequest(e) => e.sequence_number,
+        CirculateNotify(e) => e.sequence_number,
+        CirculateRequest(e) => e.sequence_number,
+        PropertyNotify(e) => e.sequence_number,
+        SelectionClear(e) => e.sequence_number,
+        SelectionRequest(e) => e.sequence_number,
+        SelectionNotify(e) => e.sequence_number,
+        ColormapNotify(e) => e.sequence_number,
+        ClientMessage(e) => e.sequence_number,
+        MappingNotify(e) => e.sequence_number,
+        Generic(e) => e.sequence_number,
+        // the sequence number follows the code and detail bytes in every extension event
+        UnknownCore(data) | Ext(data) => u16::from_be_bytes([*data.get(1)?, *data.get(2)?]),
+    })
+}
+
 impl X11Connection {
     pub fn events<'a>(&'a self) -> EventReceiver<'a> {
         EventReceiver {
@@ -5,12 +5,26 @@ use super::*;
 #[derive(Clone, Copy, Debug)]
 pub enum Extension {
     Xge,
+    BigRequests,
     Shape,
     XFixes,
     XInput,
     XKB,
     XRandr,
     XRecord,
+    Render,
+    Shm,
+    Dpms,
+    DRI2,
+    Composite,
+    Damage,
+    Present,
+    Sync,
+    XTest,
+    Xinerama,
+    ScreenSaver,
+    XRes,
+    DRI3,
     Unknown,
 }
 
@@ -21,6 +35,8 @@ pub(crate) struct ExtInfo {
     pub event_start: u8,
     pub error_start: u8,
     pub event_count: u8,
+    // negotiated major and minor version, None until QueryVersion and for extensions without one (BIG-REQUESTS)
+    pub version: Option<(u32, u32)>,
 }
 
 impl X11Connection {
@@ -38,4 +54,19 @@ impl X11Connection {
             value.event_start <= code && value.event_start + value.event_count > code
         })
     }
+
+    pub(crate) fn set_ext_version(&self, ext_name: &str, major: impl Into<u32>, minor: impl Into<u32>) {
+        if let Some(mut info) = self.0.registered_extensions.get_mut(ext_name) {
+            info.version = Some((major.into(), minor.into()));
+        }
+    }
+
+    /// Fails if the server's version of `ext_name` is below `major`.`minor`, which `request` needs
+    pub(crate) fn require_ext_version(&self, ext_name: &str, major: u32, minor: u32, request: &str) -> Result<()> {
+        let version = self.get_ext_info(ext_name).and_then(|x| x.version).unwrap_or((0, 0));
+        if version < (major, minor) {
+            bail!("{} requires {} {}.{}, server supports {}.{}", request, ext_name, major, minor, version.0, version.1);
+        }
+        Ok(())
+    }
 }
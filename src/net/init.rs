@@ -1,53 +1,127 @@
 use crate::{
     coding::{ClientHandshake, Response, ResponseBody, ServerHandshake, ServerHandshakeBody},
-    connection::{TcpConnection, UnixConnection},
+    connection::{AuthInfo, DisplayName, DisplayTransport, FdQueues, TcpConnection, UnixConnection},
 };
 
 use super::*;
 use dashmap::mapref::entry::Entry;
 use tokio::{
     io::{AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, BufWriter},
-    sync::{broadcast, mpsc, Mutex},
+    sync::{broadcast, mpsc, mpsc::error::TryRecvError, watch, Mutex},
 };
 
+fn lookup_auth(auth: Result<Option<AuthInfo>>) -> Option<AuthInfo> {
+    match auth {
+        Ok(auth) => auth,
+        Err(e) => {
+            warn!("failed to load xauthority, connecting without auth: {:?}", e);
+            None
+        }
+    }
+}
+
 impl X11Connection {
-    async fn writer_thread(mut writer: BufWriter<impl AsyncWrite + Unpin + Send + Sync>, mut in_receiver: mpsc::Receiver<RequestLen>) -> Result<()> {
-        while let Some(request) = in_receiver.recv().await {
-            request.request.encode_async(&mut writer, request.len).await?;
-            writer.flush().await?;
+    async fn writer_thread(
+        mut writer: BufWriter<impl AsyncWrite + Unpin + Send + Sync>,
+        mut in_receiver: mpsc::Receiver<WriterMessage>,
+        mut state: watch::Receiver<ConnectionState>,
+        fds: Option<Arc<FdQueues>>,
+    ) -> Result<()> {
+        // set when a written request asked to be flushed, deferred until no more requests are queued
+        let mut needs_flush = false;
+        loop {
+            let message = match in_receiver.try_recv() {
+                Ok(message) => Some(message),
+                Err(TryRecvError::Empty) => {
+                    if needs_flush {
+                        writer.flush().await?;
+                        needs_flush = false;
+                    }
+                    tokio::select! {
+                        message = in_receiver.recv() => message,
+                        // the reader failed
+                        _ = state.changed() => return Ok(()),
+                    }
+                }
+                Err(TryRecvError::Disconnected) => None,
+            };
+            match message {
+                Some(WriterMessage::Request(request)) => {
+                    // queued before the request bytes, so they're sent no later than the request.
+                    // requests only carry fds if the connection has somewhere to put them
+                    if let Some(fds) = &fds {
+                        if !request.fds.is_empty() {
+                            fds.outgoing.lock().unwrap().extend(request.fds);
+                        }
+                    }
+                    request.request.encode_async(&mut writer, request.len).await?;
+                    needs_flush |= request.flush;
+                }
+                Some(WriterMessage::Flush(ack)) => {
+                    writer.flush().await?;
+                    needs_flush = false;
+                    let _ = ack.send(());
+                }
+                Some(WriterMessage::Close(ack)) => {
+                    let result = async {
+                        writer.flush().await?;
+                        writer.shutdown().await?;
+                        Ok(())
+                    }
+                    .await;
+                    let _ = ack.send(result);
+                    return Ok(());
+                }
+                None => {
+                    writer.flush().await?;
+                    return Ok(());
+                }
+            }
         }
-        Ok(())
     }
 
     async fn reader_thread(
         mut reader: BufReader<impl AsyncRead + Unpin + Send + Sync>,
         output: Arc<X11OutputContext>,
-        events: broadcast::Sender<(u8, crate::coding::Event)>,
+        events: broadcast::Sender<RawEventData>,
     ) -> Result<()> {
+        let mut state = output.state_receiver.clone();
         loop {
-            let response = Response::decode_async(&mut reader).await?;
+            let response = tokio::select! {
+                response = Response::decode_async(&mut reader) => response?,
+                // closed by us or the writer failed
+                _ = state.changed() => return Ok(()),
+            };
             match response.body {
                 ResponseBody::Event(event) => {
-                    if let Err(_) = events.send((response.code, event)) {
+                    let seq = match raw_sequence_number(&event) {
+                        Some(sequence_number) => output.widen_sequence(sequence_number),
+                        // KeymapNotify follows the event it belongs to
+                        None => output.last_read.load(Ordering::SeqCst),
+                    };
+                    if let Err(_) = events.send((response.code, seq, event)) {
                         warn!("failed to send x11 event (no listeners)");
                     }
                 }
                 ResponseBody::ErrorReply(error) => {
-                    let entry = output.responses.entry(error.sequence_number);
+                    let seq = output.widen_sequence(error.sequence_number);
+                    output.retire_voids(seq).await;
+                    let entry = output.responses.entry(seq);
                     let pending_error = match entry {
                         Entry::Vacant(_) => {
-                            warn!("received unexpected error for seq {}: {:?}", error.sequence_number, error);
+                            warn!("received unexpected error for seq {}: {:?}", seq, error);
                             Some(error)
                         }
                         Entry::Occupied(mut occupied) => match &mut *occupied.get_mut() {
                             ResponseValue::InboundVoidError => {
                                 debug!("inbound x11 error: {:?} <{}>", error.code, error.bad_value);
+                                occupied.remove();
                                 Some(error)
                             }
-                            ResponseValue::Single(_) => {
+                            ResponseValue::Single(_) | ResponseValue::CheckedVoid(_) => {
                                 let sender = occupied.remove();
                                 match sender {
-                                    ResponseValue::Single(sender) => {
+                                    ResponseValue::Single(sender) | ResponseValue::CheckedVoid(sender) => {
                                         let _ = sender.send(Response {
                                             code: response.code,
                                             body: ResponseBody::ErrorReply(error),
@@ -57,6 +131,18 @@ impl X11Connection {
                                 }
                                 None
                             }
+                            ResponseValue::SingleFds(_) => {
+                                if let ResponseValue::SingleFds(sender) = occupied.remove() {
+                                    let _ = sender.send((
+                                        Response {
+                                            code: response.code,
+                                            body: ResponseBody::ErrorReply(error),
+                                        },
+                                        vec![],
+                                    ));
+                                }
+                                None
+                            }
                             ResponseValue::Stream(sender) => {
                                 if sender
                                     .send(Response {
@@ -73,17 +159,19 @@ impl X11Connection {
                         },
                     };
                     if let Some(pending_error) = pending_error {
-                        output.pending_errors.lock().await.push(pending_error);
+                        output.pending_errors.lock().await.push((seq, pending_error));
                     }
                 }
                 ResponseBody::Reply(reply) => {
-                    let entry = output.responses.entry(reply.sequence_number);
+                    let seq = output.widen_sequence(reply.sequence_number);
+                    output.retire_voids(seq).await;
+                    let entry = output.responses.entry(seq);
                     match entry {
                         Entry::Vacant(_) => {
-                            warn!("received unexpected reply for seq {}: {:?}", reply.sequence_number, reply);
+                            warn!("received unexpected reply for seq {}: {:?}", seq, reply);
                         }
                         Entry::Occupied(mut occupied) => match &mut *occupied.get_mut() {
-                            ResponseValue::InboundVoidError => {
+                            ResponseValue::InboundVoidError | ResponseValue::CheckedVoid(_) => {
                                 warn!("received unexpected reply to void request: {:?}", reply);
                             }
                             ResponseValue::Single(_) => {
@@ -98,6 +186,18 @@ impl X11Connection {
                                     _ => unimplemented!(),
                                 }
                             }
+                            ResponseValue::SingleFds(_) => {
+                                let fds = output.take_fds(reply.reserved as usize);
+                                if let ResponseValue::SingleFds(sender) = occupied.remove() {
+                                    let _ = sender.send((
+                                        Response {
+                                            code: response.code,
+                                            body: ResponseBody::Reply(reply),
+                                        },
+                                        fds,
+                                    ));
+                                }
+                            }
                             ResponseValue::Stream(sender) => {
                                 if sender
                                     .send(Response {
@@ -118,27 +218,94 @@ impl X11Connection {
     }
 
     pub async fn connect(host: &str, display: u16) -> Result<Self> {
+        Self::connect_to(&DisplayName::new(host, display), None).await
+    }
+
+    /// Connects like [`X11Connection::connect`], but sends `auth` instead of consulting the Xauthority file
+    pub async fn connect_with_auth(host: &str, display: u16, auth: AuthInfo) -> Result<Self> {
+        Self::connect_to(&DisplayName::new(host, display), Some(auth)).await
+    }
+
+    /// Connects to the display named by `$DISPLAY`
+    pub async fn connect_from_env() -> Result<Self> {
+        Self::connect_to(&DisplayName::from_env()?, None).await
+    }
+
+    /// Connects to a display name of the form `[protocol/][host]:display[.screen]`
+    pub async fn connect_display(name: &str) -> Result<Self> {
+        Self::connect_to(&DisplayName::parse(name)?, None).await
+    }
+
+    /// Connects to `name`, using `auth` if set and the Xauthority file otherwise
+    pub async fn connect_to(name: &DisplayName, auth: Option<AuthInfo>) -> Result<Self> {
+        let screen = name.screen as usize;
         #[cfg(not(target_os = "windows"))]
-        if host == "" || host == "unix" {
-            if let Ok(c) = UnixConnection::connect(display).await {
-                let (writer, reader) = c.into_split();
-                return Self::open(reader, writer).await;
+        if name.transport()? == DisplayTransport::Unix {
+            let connection = match &name.socket_path {
+                Some(path) => UnixConnection::connect_socket(path).await,
+                None => UnixConnection::connect(name.display).await,
+            };
+            match connection {
+                Ok(c) => {
+                    let auth = match auth {
+                        Some(auth) => Some(auth),
+                        None => lookup_auth(AuthInfo::from_xauthority_local(name.display).await),
+                    };
+                    let (reader, writer, fds) = c.into_fd_split();
+                    return Self::open_internal(writer, reader, auth, screen, Some(fds)).await;
+                }
+                // an explicit unix transport has nothing to fall back to
+                Err(e) if name.protocol.is_some() || name.socket_path.is_some() => return Err(e),
+                Err(e) => debug!("unix connection to display {} failed, trying tcp: {:?}", name, e),
             }
         }
-        let connection = TcpConnection::connect(host, display).await?;
+        let host = match &*name.host {
+            "" | "unix" => "localhost",
+            host => host,
+        };
+        let connection = TcpConnection::connect(host, name.display).await?;
+        let auth = match auth {
+            Some(auth) => Some(auth),
+            None => lookup_auth(AuthInfo::from_xauthority_tcp(connection.peer_addr()?.ip(), name.display).await),
+        };
         let (writer, reader) = connection.into_split();
-        Self::open(reader, writer).await
+        Self::open_internal(reader, writer, auth, screen, None).await
     }
 
     pub async fn open(writer: impl AsyncWrite + Unpin + Send + Sync + 'static, reader: impl AsyncRead + Unpin + Send + Sync + 'static) -> Result<Self> {
+        Self::open_internal(writer, reader, None, 0, None).await
+    }
+
+    pub async fn open_with_auth(
+        writer: impl AsyncWrite + Unpin + Send + Sync + 'static,
+        reader: impl AsyncRead + Unpin + Send + Sync + 'static,
+        auth: Option<AuthInfo>,
+    ) -> Result<Self> {
+        Self::open_internal(writer, reader, auth, 0, None).await
+    }
+
+    async fn open_internal(
+        writer: impl AsyncWrite + Unpin + Send + Sync + 'static,
+        reader: impl AsyncRead + Unpin + Send + Sync + 'static,
+        auth: Option<AuthInfo>,
+        default_screen: usize,
+        fds: Option<Arc<FdQueues>>,
+    ) -> Result<Self> {
         let mut writer = BufWriter::new(writer);
         let mut reader = BufReader::new(reader);
+        let (auth_proto_name, auth_proto_data) = match auth {
+            Some(auth) => {
+                info!("authenticating with {}", auth.name);
+                (auth.name, auth.data)
+            }
+            None => (String::new(), vec![]),
+        };
         let handshake = ClientHandshake {
             byte_order: 0x42,
             protocol_major_version: PROTOCOL_MAJOR_VERSION,
             protocol_minor_version: PROTOCOL_MINOR_VERSION,
-            auth_proto_name: "".to_string(),
-            auth_proto_data: "".to_string(),
+            auth_proto_name,
+            auth_proto_data,
             ..Default::default()
         };
         info!("sending handshake");
@@ -159,16 +326,30 @@ impl X11Connection {
             }
             ServerHandshakeBody::Success(packet) => packet,
         };
+        if default_screen >= handshake.screens.len() {
+            bail!("invalid screen {}, server has {} screens", default_screen, handshake.screens.len());
+        }
 
+        let (state_sender, state_receiver) = watch::channel(ConnectionState::Open);
         let output = Arc::new(X11OutputContext {
             pending_errors: Mutex::new(vec![]),
             responses: DashMap::new(),
+            last_read: AtomicU64::new(0),
+            last_written: AtomicU64::new(0),
+            pending_voids: Mutex::new(VecDeque::new()),
+            state: state_sender,
+            state_receiver,
+            closed: AtomicBool::new(false),
+            fds: fds.clone(),
         });
 
-        let (in_sender, in_receiver) = mpsc::channel::<RequestLen>(10);
+        let (in_sender, in_receiver) = mpsc::channel::<WriterMessage>(10);
+        let output2 = output.clone();
+        let state = output.state_receiver.clone();
         tokio::spawn(async move {
-            if let Err(e) = Self::writer_thread(writer, in_receiver).await {
+            if let Err(e) = Self::writer_thread(writer, in_receiver, state, fds).await {
                 error!("x11 writing failed: {:?}", e);
+                output2.shutdown(CloseReason::from_error(&e)).await;
             }
         });
 
@@ -177,22 +358,31 @@ impl X11Connection {
         let output2 = output.clone();
         let events_sender2 = events_sender.clone();
         tokio::spawn(async move {
-            if let Err(e) = Self::reader_thread(reader, output2, events_sender2).await {
-                error!("x11 writing failed: {:?}", e);
+            if let Err(e) = Self::reader_thread(reader, output2.clone(), events_sender2).await {
+                match CloseReason::from_error(&e) {
+                    CloseReason::Eof => info!("x11 connection closed by server"),
+                    _ => error!("x11 reading failed: {:?}", e),
+                }
+                output2.shutdown(CloseReason::from_error(&e)).await;
             }
         });
 
+        let maximum_request_length = handshake.maximum_request_length as u32;
         let mut self_ = Self(Arc::new(X11ConnectionInterior {
             output,
             write_data: Mutex::new(WriteData {
                 seq: 1,
+                last_reply_seq: 0,
                 writer: in_sender,
             }),
             handshake,
+            default_screen,
             next_resource_id: AtomicU32::new(0),
+            maximum_request_length: AtomicU32::new(maximum_request_length),
             known_atoms: DashMap::new(),
             known_atoms_inverse: DashMap::new(),
             registered_extensions: DashMap::new(),
+            shm_version: Default::default(),
             events_sender,
         }));
         self_.register_const_atoms();
@@ -1,66 +1,199 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    future::Future,
+    ops::{Deref, DerefMut},
+};
 
-use anyhow::Context;
 use dashmap::mapref::entry::Entry;
 use futures::{Stream, StreamExt};
 use tokio_stream::wrappers::ReceiverStream;
 
-use crate::coding::{Request, RequestBody, Response, ResponseBody};
+use crate::coding::{MajorOpcode, Request, RequestBody, Response, ResponseBody};
+use crate::connection::OwnedFd;
 
 use super::*;
 
 pub(crate) struct RequestLen {
     pub request: Request,
     pub len: u64,
+    // whether the writer should flush once it runs out of queued requests
+    pub flush: bool,
+    // passed along with the request, only on unix sockets
+    pub fds: Vec<OwnedFd>,
+}
+
+pub(crate) enum WriterMessage {
+    Request(RequestLen),
+    Flush(oneshot::Sender<()>),
+    // flush and shut down the write half, then acknowledge
+    Close(oneshot::Sender<Result<()>>),
 }
 
 pub enum ResponseType {
     Void,
+    CheckedVoid(oneshot::Sender<Response>),
     Single(oneshot::Sender<Response>),
+    /// a reply carrying file descriptors, as many as its reserved byte says
+    SingleFds(oneshot::Sender<(Response, Vec<OwnedFd>)>),
     Stream(mpsc::Sender<Response>),
 }
 
+tokio::task_local! {
+    // set inside `X11Connection::checked`
+    static CHECKED: bool;
+    // set inside `X11Connection::batch`
+    static BATCH: bool;
+    // set inside `X11Connection::send_split`, checked void requests are collected here to be awaited after a single round-trip
+    static DEFERRED_CHECKS: std::sync::Mutex<Vec<(u64, oneshot::Receiver<Response>)>>;
+}
+
+/// Number of void requests sent in a row before we insert a round-trip, so their slots can be retired
+const VOID_SYNC_INTERVAL: u64 = 1024;
+
 impl X11Connection {
-    pub async fn send_request(&self, major_opcode: u8, minor_opcode: u8, type_: ResponseType, body: RequestBody) -> Result<()> {
+    /// Sends a request, returning its sequence number
+    pub async fn send_request(&self, major_opcode: u8, minor_opcode: u8, type_: ResponseType, body: RequestBody) -> Result<u64> {
+        self.send_request_with_fds(major_opcode, minor_opcode, type_, body, vec![]).await
+    }
+
+    /// Sends a request along with file descriptors, which requires a unix socket connection
+    pub async fn send_request_with_fds(&self, major_opcode: u8, minor_opcode: u8, type_: ResponseType, body: RequestBody, fds: Vec<OwnedFd>) -> Result<u64> {
+        if !fds.is_empty() && self.0.output.fds.is_none() {
+            bail!("file descriptors can only be passed over unix socket connections");
+        }
         let mut data = vec![];
         body.encode_sync(&mut data, major_opcode, minor_opcode, 0)?;
-        let mut length = data.len() as u32 + 4;
-        let out_len = if length % 4 != 0 { (length + 4 - (length % 4)) / 4 } else { length / 4 };
-        length -= 4;
+        let length = data.len() as u32;
+        // in 4-byte units, including the header
+        let mut out_len = (length + 4 + 3) / 4;
+        if out_len > u16::MAX as u32 {
+            // big requests carry an extra 4-byte length field
+            out_len += 1;
+        }
+        let maximum_length = self.maximum_request_length();
+        if out_len > maximum_length {
+            return Err(RequestTooLarge {
+                major_opcode,
+                minor_opcode,
+                length: out_len,
+                maximum_length,
+            }
+            .into());
+        }
 
         let request = Request {
             major_opcode,
             minor_opcode,
-            length: if out_len < u16::MAX as u32 { out_len as u16 } else { 0 },
-            ext_length: if out_len >= u16::MAX as u32 { Some(out_len) } else { None },
+            length: if out_len <= u16::MAX as u32 { out_len as u16 } else { 0 },
+            ext_length: if out_len > u16::MAX as u32 { Some(out_len) } else { None },
             data,
         };
         let mut write_data = self.0.write_data.lock().await;
+        let response = match type_ {
+            ResponseType::Void => ResponseValue::InboundVoidError,
+            ResponseType::CheckedVoid(sender) => ResponseValue::CheckedVoid(sender),
+            ResponseType::Single(sender) => ResponseValue::Single(sender),
+            ResponseType::SingleFds(sender) => ResponseValue::SingleFds(sender),
+            ResponseType::Stream(sender) => ResponseValue::Stream(sender),
+        };
+        if response.is_void() && write_data.seq - write_data.last_reply_seq >= VOID_SYNC_INTERVAL {
+            // the reply retires the pending void requests, and keeps sequence numbers from wrapping without a response
+            self.send_sync(&mut write_data).await?;
+        }
+        // batched void requests are only written out when the buffer fills, a reply is awaited, or on `flush`
+        let flush = !(response.is_void() && BATCH.try_with(|batch| *batch).unwrap_or(false));
+        let seq = self.enqueue_request(&mut write_data, request, length as u64, response, flush, fds).await?;
+        drop(write_data);
+
+        Ok(seq)
+    }
+
+    async fn enqueue_request(
+        &self,
+        write_data: &mut WriteData,
+        request: Request,
+        len: u64,
+        response: ResponseValue,
+        flush: bool,
+        fds: Vec<OwnedFd>,
+    ) -> Result<u64> {
         let seq = write_data.seq;
         write_data.seq += 1;
-        self.0.output.responses.insert(
-            seq,
-            match type_ {
-                ResponseType::Void => ResponseValue::InboundVoidError,
-                ResponseType::Single(sender) => ResponseValue::Single(sender),
-                ResponseType::Stream(sender) => ResponseValue::Stream(sender),
-            },
-        );
-        write_data
+        self.0.output.last_written.store(seq, Ordering::SeqCst);
+        if response.is_void() {
+            self.0.output.pending_voids.lock().await.push_back(seq);
+        } else {
+            write_data.last_reply_seq = seq;
+        }
+        self.0.output.responses.insert(seq, response);
+        // checked after inserting, so either we see the connection closed or `shutdown` clears our slot
+        if self.0.output.is_closed() {
+            self.0.output.responses.remove(&seq);
+            return Err(self.0.output.connection_lost().into());
+        }
+        if write_data
             .writer
-            .send(RequestLen {
+            .send(WriterMessage::Request(RequestLen {
                 request,
-                len: length as u64,
-            })
+                len,
+                flush,
+                fds,
+            }))
             .await
-            .ok()
-            .ok_or_else(|| anyhow!("x11 connection dead"))?;
+            .is_err()
+        {
+            self.0.output.responses.remove(&seq);
+            return Err(self.0.output.connection_lost().into());
+        }
+        Ok(seq)
+    }
+
+    /// Sends a GetInputFocus request and discards the reply
+    async fn send_sync(&self, write_data: &mut WriteData) -> Result<()> {
+        let mut data = vec![];
+        RequestBody::GetInputFocus(Default::default()).encode_sync(&mut data, MajorOpcode::GetInputFocus as u8, 0, 0)?;
+        let request = Request {
+            major_opcode: MajorOpcode::GetInputFocus as u8,
+            minor_opcode: 0,
+            length: ((data.len() + 4 + 3) / 4) as u16,
+            ext_length: None,
+            data,
+        };
+        let len = request.data.len() as u64;
+        // the receiver is dropped, so the reply is discarded when it arrives
+        let (sender, _) = oneshot::channel();
+        self.enqueue_request(write_data, request, len, ResponseValue::Single(sender), false, vec![])
+            .await?;
+        Ok(())
+    }
+
+    /// Writes out all requests sent so far
+    pub async fn flush(&self) -> Result<()> {
+        let (sender, receiver) = oneshot::channel();
+        let write_data = self.0.write_data.lock().await;
+        let sent = write_data.writer.send(WriterMessage::Flush(sender)).await.is_ok();
         drop(write_data);
+        if !sent || receiver.await.is_err() {
+            return Err(self.0.output.connection_lost().into());
+        }
+        Ok(())
+    }
+
+    /// Runs `future` without flushing after each void request it sends, then flushes.
+    /// Requests expecting a reply are still flushed, since their reply is awaited.
+    pub async fn batch<F: Future<Output = Result<T>>, T>(&self, future: F) -> Result<T> {
+        let out = BATCH.scope(true, future).await;
+        self.flush().await?;
+        out
+    }
 
+    /// Round-trips to the server. Once this returns, any errors caused by previously sent requests have been received.
+    pub async fn sync(&self) -> Result<()> {
+        self.send_request_single(MajorOpcode::GetInputFocus as u8, 0, RequestBody::GetInputFocus(Default::default()), |_, _| Ok(()))
+            .await?;
         Ok(())
     }
 
-    pub async fn end_stream(&self, seq: u16) -> Result<()> {
+    pub async fn end_stream(&self, seq: u64) -> Result<()> {
         match self.0.output.responses.entry(seq) {
             Entry::Occupied(entry) => match entry.get() {
                 ResponseValue::Stream(_) => {
@@ -74,7 +207,106 @@ impl X11Connection {
     }
 
     pub async fn send_request_void(&self, major_opcode: u8, minor_opcode: u8, body: RequestBody) -> Result<()> {
-        self.send_request(major_opcode, minor_opcode, ResponseType::Void, body).await
+        self.send_request_void_fds(major_opcode, minor_opcode, body, vec![]).await
+    }
+
+    /// Sends a void request along with file descriptors, which requires a unix socket connection
+    pub async fn send_request_void_fds(&self, major_opcode: u8, minor_opcode: u8, body: RequestBody, fds: Vec<OwnedFd>) -> Result<()> {
+        if CHECKED.try_with(|checked| *checked).unwrap_or(false) {
+            if DEFERRED_CHECKS.try_with(|_| ()).is_ok() {
+                let (sender, receiver) = oneshot::channel();
+                let seq = self
+                    .send_request_with_fds(major_opcode, minor_opcode, ResponseType::CheckedVoid(sender), body, fds)
+                    .await?;
+                DEFERRED_CHECKS.with(|deferred| deferred.lock().unwrap().push((seq, receiver)));
+                return Ok(());
+            }
+            return self
+                .send_request_void_checked_fds(major_opcode, minor_opcode, body, fds)
+                .await
+                .map_err(|e| match e {
+                    X11Error::Error(e) => e,
+                    X11Error::X11Error(e) => e.into(),
+                });
+        }
+        self.send_request_with_fds(major_opcode, minor_opcode, ResponseType::Void, body, fds).await?;
+        Ok(())
+    }
+
+    /// Sends a void request and round-trips to the server, returning the error caused by this request, if any
+    pub async fn send_request_void_checked(&self, major_opcode: u8, minor_opcode: u8, body: RequestBody) -> Result<(), X11Error> {
+        self.send_request_void_checked_fds(major_opcode, minor_opcode, body, vec![]).await
+    }
+
+    async fn send_request_void_checked_fds(&self, major_opcode: u8, minor_opcode: u8, body: RequestBody, fds: Vec<OwnedFd>) -> Result<(), X11Error> {
+        let (sender, receiver) = oneshot::channel();
+        let seq = self
+            .send_request_with_fds(major_opcode, minor_opcode, ResponseType::CheckedVoid(sender), body, fds)
+            .await?;
+        self.sync().await?;
+        // the sender is dropped without a response when the request completed successfully
+        match receiver.await {
+            Ok(response) => match response.body {
+                ResponseBody::ErrorReply(e) => Err(X11Error::X11Error(X11ErrorReply::from_raw(self, seq, &e))),
+                _ => Err(anyhow!("unexpected reply for void request").into()),
+            },
+            Err(_) => Ok(()),
+        }
+    }
+
+    /// Sends the void requests a single call was split into, batched. Errors are reported like those of an unsplit request: inside
+    /// [`X11Connection::checked`] they are collected with a single round-trip at the end and returned, as a [`SplitRequestError`]
+    /// if more than one request was sent, otherwise they are left for [`X11Connection::check_errors`].
+    pub(crate) async fn send_split<F: Future<Output = Result<()>>>(&self, requests: impl IntoIterator<Item = F>) -> Result<()> {
+        let mut total = 0;
+        let mut failures = vec![];
+        let mut pending = vec![];
+        let sends = async {
+            for request in requests {
+                if let Err(e) = request.await {
+                    failures.push((total, e));
+                }
+                let checks = DEFERRED_CHECKS.with(|deferred| std::mem::take(&mut *deferred.lock().unwrap()));
+                pending.extend(checks.into_iter().map(|(seq, receiver)| (total, seq, receiver)));
+                total += 1;
+            }
+            Ok(())
+        };
+        self.batch(DEFERRED_CHECKS.scope(Default::default(), sends)).await?;
+
+        if !pending.is_empty() {
+            self.sync().await?;
+            for (index, seq, receiver) in pending {
+                // the sender is dropped without a response when the request completed successfully
+                if let Ok(Response {
+                    body: ResponseBody::ErrorReply(e),
+                    ..
+                }) = receiver.await
+                {
+                    failures.push((index, X11ErrorReply::from_raw(self, seq, &e).into()));
+                }
+            }
+            failures.sort_by_key(|(index, _)| *index);
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else if total == 1 {
+            Err(failures.pop().unwrap().1)
+        } else {
+            Err(SplitRequestError {
+                total,
+                failures,
+            }
+            .into())
+        }
+    }
+
+    /// Runs `future`, checking every void request it sends like [`X11Connection::send_request_void_checked`].
+    /// An error caused by one of those requests is returned from the request's call as an [`X11ErrorReply`],
+    /// i.e. `connection.checked(window.reparent(parent, 0, 0)).await?`.
+    pub async fn checked<F: Future>(&self, future: F) -> F::Output {
+        CHECKED.scope(true, future).await
     }
 
     pub async fn send_request_single<T>(
@@ -85,18 +317,39 @@ impl X11Connection {
         decoder: fn(&mut &[u8], u8) -> Result<T>,
     ) -> Result<T, X11Error> {
         let (sender, receiver) = oneshot::channel();
-        self.send_request(major_opcode, minor_opcode, ResponseType::Single(sender), body).await?;
-        let response = receiver.await.context("sender dropped")?;
+        let seq = self.send_request(major_opcode, minor_opcode, ResponseType::Single(sender), body).await?;
+        let response = match receiver.await {
+            Ok(response) => response,
+            Err(_) => return Err(anyhow::Error::from(self.0.output.connection_lost()).into()),
+        };
         match response.body {
-            ResponseBody::ErrorReply(e) => Err(X11Error::X11Error(X11ErrorReply {
-                bad_value: e.bad_value,
-                code: X11ErrorCode::from_raw(self, e.code),
-            })),
+            ResponseBody::ErrorReply(e) => Err(X11Error::X11Error(X11ErrorReply::from_raw(self, seq, &e))),
             ResponseBody::Reply(r) => decoder(&mut &r.data[..], r.reserved).map_err(Into::into),
             ResponseBody::Event(_) => unimplemented!(),
         }
     }
 
+    /// Sends a request whose reply carries file descriptors, returning them alongside the decoded reply
+    pub async fn send_request_single_fds<T>(
+        &self,
+        major_opcode: u8,
+        minor_opcode: u8,
+        body: RequestBody,
+        decoder: fn(&mut &[u8], u8) -> Result<T>,
+    ) -> Result<(T, Vec<OwnedFd>), X11Error> {
+        let (sender, receiver) = oneshot::channel();
+        let seq = self.send_request(major_opcode, minor_opcode, ResponseType::SingleFds(sender), body).await?;
+        let (response, fds) = match receiver.await {
+            Ok(response) => response,
+            Err(_) => return Err(anyhow::Error::from(self.0.output.connection_lost()).into()),
+        };
+        match response.body {
+            ResponseBody::ErrorReply(e) => Err(X11Error::X11Error(X11ErrorReply::from_raw(self, seq, &e))),
+            ResponseBody::Reply(r) => Ok((decoder(&mut &r.data[..], r.reserved)?, fds)),
+            ResponseBody::Event(_) => Err(anyhow!("unexpected event in place of a reply").into()),
+        }
+    }
+
     pub async fn send_request_stream<'a, T: Send + Sync + 'static>(
         &'a self,
         major_opcode: u8,
@@ -105,12 +358,9 @@ impl X11Connection {
         decoder: fn(&mut &[u8], u8) -> Result<T>,
     ) -> Result<impl Stream<Item = Result<T, X11Error>> + 'a> {
         let (sender, receiver) = mpsc::channel(5);
-        self.send_request(major_opcode, minor_opcode, ResponseType::Stream(sender), body).await?;
+        let seq = self.send_request(major_opcode, minor_opcode, ResponseType::Stream(sender), body).await?;
         Ok(ReceiverStream::new(receiver).map(move |response| match response.body {
-            ResponseBody::ErrorReply(e) => Err(X11Error::X11Error(X11ErrorReply {
-                bad_value: e.bad_value,
-                code: X11ErrorCode::from_raw(self, e.code),
-            })),
+            ResponseBody::ErrorReply(e) => Err(X11Error::X11Error(X11ErrorReply::from_raw(self, seq, &e))),
             ResponseBody::Reply(r) => decoder(&mut &r.data[..], r.reserved).map_err(Into::into),
             ResponseBody::Event(_) => unimplemented!(),
         }))
@@ -300,6 +550,34 @@ macro_rules! send_request_ext {
             $self_.send_request_single($ext_code as u8, $opcode as u8, body, |data, reply_reserved| $reply::decode_sync(data, reply_reserved).map(|inner| $crate::net::ReservedWrapper::new(inner, reply_reserved))).await?
         }
     };
+    ($self_:expr, $ext_code:expr, $opcode:expr, fds $fds:expr, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let body = $name {
+                $($key: $value,)*
+                ..Default::default()
+            };
+            let mut buf_out = vec![];
+            body.encode_sync(&mut buf_out)?;
+            let body = RequestBody::Ext($crate::coding::ExtRequest {
+                data: buf_out,
+            });
+            $self_.send_request_void_fds($ext_code as u8, $opcode as u8, body, $fds).await?
+        }
+    };
+    ($self_:expr, $ext_code:expr, $opcode:expr, receive_fds $reply:ident, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let body = $name {
+                $($key: $value,)*
+                ..Default::default()
+            };
+            let mut buf_out = vec![];
+            body.encode_sync(&mut buf_out)?;
+            let body = RequestBody::Ext($crate::coding::ExtRequest {
+                data: buf_out,
+            });
+            $self_.send_request_single_fds($ext_code as u8, $opcode as u8, body, |data, reply_reserved| $reply::decode_sync(data).map(|inner| $crate::net::ReservedWrapper::new(inner, reply_reserved))).await?
+        }
+    };
     ($self_:expr, $ext_code:expr, $opcode:expr, stream, $reply:ident, $name:ident { $($key:ident: $value:expr,)* }) => {
         {
             let body = $name {
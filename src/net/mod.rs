@@ -1,14 +1,15 @@
 use std::{
+    collections::VecDeque,
     fmt,
     sync::{
-        atomic::{AtomicU32, Ordering},
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
         Arc,
     },
 };
 
 use anyhow::Result;
 use dashmap::DashMap;
-use tokio::sync::{broadcast, mpsc, oneshot, Mutex};
+use tokio::sync::{broadcast, mpsc, oneshot, watch, Mutex};
 
 mod errors;
 pub use errors::*;
@@ -28,35 +29,137 @@ pub use event::*;
 pub use crate::coding::x11::{Endianness, PixmapFormat};
 use crate::{
     coding::{ErrorReply, Response, ServerHandshakeSuccess},
-    requests::Screen,
+    connection::{FdQueues, OwnedFd},
+    requests::{Screen, ShmVersion},
 };
 
 enum ResponseValue {
     InboundVoidError,
+    // void request whose error is delivered to the sender instead of `pending_errors`, dropped when retired
+    CheckedVoid(oneshot::Sender<Response>),
     Single(oneshot::Sender<Response>),
+    // reply carrying as many file descriptors as its reserved byte says
+    SingleFds(oneshot::Sender<(Response, Vec<OwnedFd>)>),
     Stream(mpsc::Sender<Response>),
 }
 
+impl ResponseValue {
+    fn is_void(&self) -> bool {
+        matches!(self, ResponseValue::InboundVoidError | ResponseValue::CheckedVoid(_))
+    }
+}
+
 struct X11OutputContext {
-    pending_errors: Mutex<Vec<ErrorReply>>,
-    responses: DashMap<u16, ResponseValue>,
+    // errors for void requests, with their widened sequence number
+    pending_errors: Mutex<Vec<(u64, ErrorReply)>>,
+    // keyed by the widened sequence number, see `X11OutputContext::widen_sequence`
+    responses: DashMap<u64, ResponseValue>,
+    // widened sequence number of the last reply, error or event read
+    last_read: AtomicU64,
+    // widened sequence number of the last request queued, no response can be for a later one
+    last_written: AtomicU64,
+    // sequence numbers of void requests still in `responses`, in order
+    pending_voids: Mutex<VecDeque<u64>>,
+    state: watch::Sender<ConnectionState>,
+    // kept so the state can always be sent, and cloned for watchers
+    state_receiver: watch::Receiver<ConnectionState>,
+    closed: AtomicBool,
+    // set for unix sockets, which can pass file descriptors
+    fds: Option<Arc<FdQueues>>,
+}
+
+impl X11OutputContext {
+    /// Reconstructs the full sequence number of a reply, error or event from the 16 bits sent on the wire.
+    /// Responses arrive in order, and we never let 65536 requests pass without one that expects a reply, so the
+    /// response is usually the first match at or after the last one read. Stream replies repeat the sequence number of
+    /// their (older) request, so a match past the last request written is taken from the previous wrap instead.
+    pub(crate) fn widen_sequence(&self, sequence_number: u16) -> u64 {
+        let last_read = self.last_read.load(Ordering::SeqCst);
+        let last_written = self.last_written.load(Ordering::SeqCst);
+        let mut widened = (last_read & !0xffff) | sequence_number as u64;
+        if widened < last_read {
+            widened += 0x10000;
+        }
+        if widened > last_written && widened >= 0x10000 {
+            widened -= 0x10000;
+        }
+        self.last_read.fetch_max(widened, Ordering::SeqCst);
+        widened
+    }
+
+    /// Drops the slots of void requests sent before `seq`, which can no longer receive an error
+    async fn retire_voids(&self, seq: u64) {
+        let mut pending_voids = self.pending_voids.lock().await;
+        while let Some(&void_seq) = pending_voids.front() {
+            if void_seq >= seq {
+                break;
+            }
+            pending_voids.pop_front();
+            self.responses.remove(&void_seq);
+        }
+    }
+
+    /// Claims the next `count` file descriptors received, which belong to the reply being processed
+    fn take_fds(&self, count: usize) -> Vec<OwnedFd> {
+        let fds = match &self.fds {
+            Some(fds) => fds,
+            None => return vec![],
+        };
+        let mut incoming = fds.incoming.lock().unwrap();
+        if incoming.len() < count {
+            warn!("reply expected {} file descriptors, only {} were received", count, incoming.len());
+        }
+        let count = count.min(incoming.len());
+        incoming.drain(..count).collect()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    fn connection_lost(&self) -> ConnectionLost {
+        let reason = match &*self.state_receiver.borrow() {
+            ConnectionState::Closed(reason) => reason.clone(),
+            ConnectionState::Open => CloseReason::Closed,
+        };
+        ConnectionLost {
+            reason,
+        }
+    }
+
+    /// Marks the connection closed, if it isn't already, and fails all outstanding requests
+    async fn shutdown(&self, reason: CloseReason) {
+        if !self.closed.swap(true, Ordering::SeqCst) {
+            let _ = self.state.send(ConnectionState::Closed(reason));
+            // dropping the senders fails their receivers with `ConnectionLost`
+            self.responses.clear();
+            self.pending_voids.lock().await.clear();
+        }
+    }
 }
 
 pub(crate) struct WriteData {
-    seq: u16,
-    writer: mpsc::Sender<RequestLen>,
+    seq: u64,
+    // last sequence number sent that expects a reply
+    last_reply_seq: u64,
+    writer: mpsc::Sender<WriterMessage>,
 }
 
 pub(crate) struct X11ConnectionInterior {
     output: Arc<X11OutputContext>,
     write_data: Mutex<WriteData>,
     next_resource_id: AtomicU32,
+    // in 4-byte units, see `X11Connection::maximum_request_length`
+    pub(crate) maximum_request_length: AtomicU32,
     pub(crate) handshake: ServerHandshakeSuccess,
-    events_sender: broadcast::Sender<(u8, RawEvent)>,
+    pub(crate) default_screen: usize,
+    events_sender: broadcast::Sender<RawEventData>,
     pub(crate) known_atoms: DashMap<&'static str, u32>,
     pub(crate) known_atoms_inverse: DashMap<u32, &'static str>,
     // map of ext name -> major opcode
     pub(crate) registered_extensions: DashMap<String, ExtInfo>,
+    // reply to mit-shm QueryVersion, which carries the shared pixmap support beyond the version
+    pub(crate) shm_version: std::sync::OnceLock<ShmVersion>,
 }
 
 #[derive(Clone)]
@@ -91,12 +194,26 @@ fn ensure_log(name: &str, result: Result<()>) {
 impl X11Connection {
     async fn init_state(&mut self) {
         ensure_log("xge", self.enable_xge().await);
+        ensure_log("big-requests", self.enable_bigreq().await);
         ensure_log("xkb", self.enable_xkb().await);
         ensure_log("xfixes", self.enable_xfixes().await);
         ensure_log("xinput2", self.enable_xinput2().await);
         ensure_log("xrandr", self.enable_xrandr().await);
         ensure_log("shape", self.enable_shape().await);
         ensure_log("xrecord", self.enable_xrecord().await);
+        ensure_log("render", self.enable_render().await);
+        ensure_log("mit-shm", self.enable_shm().await);
+        ensure_log("dpms", self.enable_dpms().await);
+        ensure_log("dri2", self.enable_dri2().await);
+        ensure_log("composite", self.enable_composite().await);
+        ensure_log("damage", self.enable_damage().await);
+        ensure_log("present", self.enable_present().await);
+        ensure_log("sync", self.enable_sync().await);
+        ensure_log("xtest", self.enable_xtest().await);
+        ensure_log("xinerama", self.enable_xinerama().await);
+        ensure_log("mit-screen-saver", self.enable_screensaver().await);
+        ensure_log("x-resource", self.enable_xres().await);
+        ensure_log("dri3", self.enable_dri3().await);
     }
 
     pub(crate) fn new_resource_id(&self) -> u32 {
@@ -112,6 +229,21 @@ impl X11Connection {
         out
     }
 
+    /// The screen selected by the display name this connection was opened with
+    pub fn default_screen_index(&self) -> usize {
+        self.0.default_screen
+    }
+
+    /// Whether the server runs on this machine, as known from a unix socket connection, which also allows passing file descriptors.
+    /// Connections from [`X11Connection::open`] are never considered local.
+    pub fn is_local(&self) -> bool {
+        self.0.output.fds.is_some()
+    }
+
+    pub fn default_screen(&self) -> Screen<'_> {
+        Screen::decode(self, self.0.handshake.screens[self.0.default_screen].clone())
+    }
+
     pub fn handshake(&self) -> HandshakeInfo<'_> {
         let handshake = &self.0.handshake;
         HandshakeInfo {
@@ -133,13 +265,43 @@ impl X11Connection {
 
     pub async fn check_errors(&self) -> Vec<X11ErrorReply> {
         let mut errors = self.0.output.pending_errors.lock().await;
-        errors
-            .drain(..)
-            .map(|e| X11ErrorReply {
-                bad_value: e.bad_value,
-                code: X11ErrorCode::from_raw(self, e.code),
-            })
-            .collect()
+        errors.drain(..).map(|(seq, e)| X11ErrorReply::from_raw(self, seq, &e)).collect()
+    }
+
+    pub fn state(&self) -> ConnectionState {
+        self.0.output.state_receiver.borrow().clone()
+    }
+
+    /// A watch of the connection state, which changes once when the connection closes
+    pub fn state_watch(&self) -> watch::Receiver<ConnectionState> {
+        self.0.output.state_receiver.clone()
+    }
+
+    /// Resolves when the connection is closed, with the reason it was closed
+    pub async fn closed(&self) -> CloseReason {
+        let mut state = self.state_watch();
+        loop {
+            if let ConnectionState::Closed(reason) = &*state.borrow() {
+                return reason.clone();
+            }
+            if state.changed().await.is_err() {
+                return self.0.output.connection_lost().reason;
+            }
+        }
+    }
+
+    /// Flushes all sent requests and closes the connection. Outstanding replies fail with [`ConnectionLost`].
+    pub async fn close(&self) -> Result<()> {
+        let (sender, receiver) = oneshot::channel();
+        let write_data = self.0.write_data.lock().await;
+        let sent = write_data.writer.send(WriterMessage::Close(sender)).await.is_ok();
+        drop(write_data);
+        let result = match sent {
+            true => receiver.await.unwrap_or(Ok(())),
+            false => Ok(()),
+        };
+        self.0.output.shutdown(CloseReason::Closed).await;
+        result
     }
 
     pub async fn log_errors(&self) {
@@ -0,0 +1,40 @@
+use std::sync::atomic::Ordering;
+
+use crate::{
+    coding::{BigReqEnableRequest, BigReqEnableResponse, RequestBody},
+    net::{ExtInfo, Extension},
+};
+
+use super::*;
+
+pub const BIGREQ_EXT_NAME: &str = "BIG-REQUESTS";
+
+impl X11Connection {
+    pub(crate) async fn enable_bigreq(&self) -> Result<()> {
+        // query_extension
+        let queried = self.query_extension(BIGREQ_EXT_NAME).await?;
+        ensure!(queried.present, "big-requests missing on x11 server");
+        self.0.registered_extensions.insert(
+            BIGREQ_EXT_NAME.to_string(),
+            ExtInfo {
+                extension: Extension::BigRequests,
+                major_opcode: queried.major_opcode,
+                event_start: queried.first_event,
+                error_start: queried.first_error,
+                event_count: 0,
+                version: None,
+            },
+        );
+
+        // enable extension
+        let reply = send_request_ext!(self, queried.major_opcode, 0, BigReqEnableResponse, BigReqEnableRequest {});
+        self.0.maximum_request_length.store(reply.maximum_request_length, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Maximum length of a request in 4-byte units, including the request header.
+    /// Raised past the handshake's `maximum_request_length` once BIG-REQUESTS is enabled.
+    pub fn maximum_request_length(&self) -> u32 {
+        self.0.maximum_request_length.load(Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{
+    coding::composite::{QueryVersionRequest, QueryVersionResponse},
+    net::{ExtInfo, Extension},
+};
+
+use super::*;
+
+impl X11Connection {
+    pub(crate) async fn enable_composite(&self) -> Result<()> {
+        // query_extension
+        let queried = self.query_extension(COMPOSITE_EXT_NAME).await?;
+        ensure!(queried.present, "composite missing on x11 server");
+        self.0.registered_extensions.insert(
+            COMPOSITE_EXT_NAME.to_string(),
+            ExtInfo {
+                extension: Extension::Composite,
+                major_opcode: queried.major_opcode,
+                event_start: queried.first_event,
+                error_start: queried.first_error,
+                event_count: COMPOSITE_EVENT_COUNT,
+                version: None,
+            },
+        );
+
+        // enable extension
+        let reply = send_request_ext!(
+            self,
+            queried.major_opcode,
+            CompositeOpcode::QueryVersion,
+            QueryVersionResponse,
+            QueryVersionRequest {
+                client_major_version: 0,
+                client_minor_version: 4,
+            }
+        );
+        if reply.major_version != 0 || reply.minor_version < 2 {
+            bail!("unsupported composite version on server: {}.{}", reply.major_version, reply.minor_version);
+        }
+        self.set_ext_version(COMPOSITE_EXT_NAME, reply.major_version, reply.minor_version);
+        Ok(())
+    }
+}
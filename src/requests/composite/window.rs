@@ -0,0 +1,127 @@
+pub use crate::coding::composite::Redirect;
+use crate::coding::composite::{
+    CreateRegionFromBorderClipRequest, GetOverlayWindowRequest, GetOverlayWindowResponse, NameWindowPixmapRequest, RedirectSubwindowsRequest,
+    RedirectWindowRequest, ReleaseOverlayWindowRequest, UnredirectSubwindowsRequest, UnredirectWindowRequest,
+};
+
+use super::*;
+
+impl<'a> Window<'a> {
+    /// Renders this window and its children offscreen. With [`Redirect::Manual`] the caller is responsible for drawing it to its parent.
+    pub async fn composite_redirect(self, update: Redirect) -> Result<()> {
+        send_request_composite!(
+            self.connection,
+            CompositeOpcode::RedirectWindow,
+            RedirectWindowRequest {
+                window: self.handle,
+                update: update,
+            }
+        );
+        Ok(())
+    }
+
+    /// Redirects all current and future children of this window
+    pub async fn composite_redirect_subwindows(self, update: Redirect) -> Result<()> {
+        send_request_composite!(
+            self.connection,
+            CompositeOpcode::RedirectSubwindows,
+            RedirectSubwindowsRequest {
+                window: self.handle,
+                update: update,
+            }
+        );
+        Ok(())
+    }
+
+    /// `update` must match the one used to redirect
+    pub async fn composite_unredirect(self, update: Redirect) -> Result<()> {
+        send_request_composite!(
+            self.connection,
+            CompositeOpcode::UnredirectWindow,
+            UnredirectWindowRequest {
+                window: self.handle,
+                update: update,
+            }
+        );
+        Ok(())
+    }
+
+    /// `update` must match the one used to redirect
+    pub async fn composite_unredirect_subwindows(self, update: Redirect) -> Result<()> {
+        send_request_composite!(
+            self.connection,
+            CompositeOpcode::UnredirectSubwindows,
+            UnredirectSubwindowsRequest {
+                window: self.handle,
+                update: update,
+            }
+        );
+        Ok(())
+    }
+
+    /// Creates a region of the visible parts of this window, including its border
+    pub async fn create_region_from_border_clip(self) -> Result<Region<'a>> {
+        let region = Region {
+            handle: self.connection.new_resource_id(),
+            connection: self.connection,
+        };
+        send_request_composite!(
+            self.connection,
+            CompositeOpcode::CreateRegionFromBorderClip,
+            CreateRegionFromBorderClipRequest {
+                region: region.handle,
+                window: self.handle,
+            }
+        );
+
+        Ok(region)
+    }
+
+    /// Names the offscreen storage of this redirected window. The pixmap keeps the current contents, a new one must be named after the
+    /// window is resized or remapped.
+    pub async fn name_window_pixmap(self) -> Result<Pixmap<'a>> {
+        let pixmap = self.connection.new_resource_id();
+        send_request_composite!(
+            self.connection,
+            CompositeOpcode::NameWindowPixmap,
+            NameWindowPixmapRequest {
+                window: self.handle,
+                pixmap: pixmap,
+            }
+        );
+
+        Ok(Pixmap {
+            handle: pixmap,
+            connection: self.connection,
+        })
+    }
+
+    /// Gets the overlay window of this window's screen, which is drawn above all windows and below the screen saver
+    pub async fn get_overlay_window(self) -> Result<Window<'a>> {
+        let reply = send_request_composite!(
+            self.connection,
+            CompositeOpcode::GetOverlayWindow,
+            GetOverlayWindowResponse,
+            GetOverlayWindowRequest {
+                window: self.handle,
+            }
+        );
+
+        Ok(Window {
+            handle: reply.overlay_window,
+            connection: self.connection,
+        })
+    }
+
+    /// Releases the overlay window of this window's screen, which is unmapped once all clients released it
+    pub async fn release_overlay_window(self) -> Result<()> {
+        send_request_composite!(
+            self.connection,
+            CompositeOpcode::ReleaseOverlayWindow,
+            ReleaseOverlayWindowRequest {
+                window: self.handle,
+            }
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{
+    coding::damage::{QueryVersionRequest, QueryVersionResponse},
+    net::{ExtInfo, Extension},
+};
+
+use super::*;
+
+impl X11Connection {
+    pub(crate) async fn enable_damage(&self) -> Result<()> {
+        // query_extension
+        let queried = self.query_extension(DAMAGE_EXT_NAME).await?;
+        ensure!(queried.present, "damage missing on x11 server");
+        self.0.registered_extensions.insert(
+            DAMAGE_EXT_NAME.to_string(),
+            ExtInfo {
+                extension: Extension::Damage,
+                major_opcode: queried.major_opcode,
+                event_start: queried.first_event,
+                error_start: queried.first_error,
+                event_count: DAMAGE_EVENT_COUNT,
+                version: None,
+            },
+        );
+
+        // enable extension
+        let reply = send_request_ext!(
+            self,
+            queried.major_opcode,
+            DamageOpcode::QueryVersion,
+            QueryVersionResponse,
+            QueryVersionRequest {
+                client_major_version: 1,
+                client_minor_version: 1,
+            }
+        );
+        if reply.major_version != 1 {
+            bail!("unsupported damage version on server: {}.{}", reply.major_version, reply.minor_version);
+        }
+        self.set_ext_version(DAMAGE_EXT_NAME, reply.major_version, reply.minor_version);
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use super::*;
+use crate::coding::damage::DamageOpcode;
+use crate::coding::RequestBody;
+
+pub const DAMAGE_EXT_NAME: &str = "DAMAGE";
+const DAMAGE_EVENT_COUNT: u8 = 1;
+
+macro_rules! send_request_damage {
+    ($self_:expr, $opcode:expr, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let ext_code = $self_.0.registered_extensions.get(DAMAGE_EXT_NAME).unwrap().major_opcode;
+            send_request_ext!($self_, ext_code, $opcode, $name { $($key: $value,)* })
+        }
+    };
+    ($self_:expr, $opcode:expr, $reply:ident, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let ext_code = $self_.0.registered_extensions.get(DAMAGE_EXT_NAME).unwrap().major_opcode;
+            send_request_ext!($self_, ext_code, $opcode, $reply, $name { $($key: $value,)* })
+        }
+    };
+    ($self_:expr, $opcode:expr, parse_reserved $reply:ident, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let ext_code = $self_.0.registered_extensions.get(DAMAGE_EXT_NAME).unwrap().major_opcode;
+            send_request_ext!($self_, ext_code, $opcode, parse_reserved $reply, $name { $($key: $value,)* })
+        }
+    };
+    ($self_:expr, $opcode:expr, stream, $reply:ident, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let ext_code = $self_.0.registered_extensions.get(DAMAGE_EXT_NAME).unwrap().major_opcode;
+            send_request_ext!($self_, ext_code, $opcode, stream, $reply, $name { $($key: $value,)* })
+        }
+    };
+}
+
+mod misc;
+pub use misc::*;
+
+mod report;
+pub use report::*;
@@ -0,0 +1,87 @@
+pub use crate::coding::damage::ReportLevel;
+use crate::coding::damage::{AddRequest, CreateRequest, DestroyRequest, SubtractRequest};
+
+use super::*;
+
+/// Tracks the areas of a drawable modified since they were last subtracted
+#[derive(Clone, Copy, derivative::Derivative)]
+#[derivative(Debug)]
+pub struct Damage<'a> {
+    pub(crate) handle: u32,
+    #[derivative(Debug = "ignore")]
+    pub(crate) connection: &'a X11Connection,
+}
+
+impl X11Connection {
+    /// Starts tracking damage to `drawable`, reported with [`DamageNotifyEvent`](crate::events::DamageNotifyEvent)s at the given level
+    pub async fn create_damage(&self, drawable: impl Into<Drawable<'_>>, level: ReportLevel) -> Result<Damage<'_>> {
+        let damage = self.new_resource_id();
+        send_request_damage!(
+            self,
+            DamageOpcode::Create,
+            CreateRequest {
+                damage: damage,
+                drawable: drawable.into().handle(),
+                level: level,
+            }
+        );
+
+        Ok(Damage {
+            handle: damage,
+            connection: self,
+        })
+    }
+
+    /// Reports `region` of `drawable` as damaged, for changes the server can't see such as direct rendering
+    pub async fn add_damage(&self, drawable: impl Into<Drawable<'_>>, region: Region<'_>) -> Result<()> {
+        send_request_damage!(
+            self,
+            DamageOpcode::Add,
+            AddRequest {
+                drawable: drawable.into().handle(),
+                region: region.handle,
+            }
+        );
+        Ok(())
+    }
+}
+
+impl<'a> Damage<'a> {
+    pub async fn destroy(self) -> Result<()> {
+        send_request_damage!(
+            self.connection,
+            DamageOpcode::Destroy,
+            DestroyRequest {
+                damage: self.handle,
+            }
+        );
+        Ok(())
+    }
+
+    /// Removes `repair` from the accumulated damage, or all of it if `None`. The removed area is stored in `parts` if set.
+    pub async fn subtract(self, repair: Option<Region<'_>>, parts: Option<Region<'_>>) -> Result<()> {
+        send_request_damage!(
+            self.connection,
+            DamageOpcode::Subtract,
+            SubtractRequest {
+                damage: self.handle,
+                repair: repair.map(|x| x.handle).unwrap_or(0),
+                parts: parts.map(|x| x.handle).unwrap_or(0),
+            }
+        );
+        Ok(())
+    }
+}
+
+impl<'a> Resource<'a> for Damage<'a> {
+    fn x11_handle(&self) -> u32 {
+        self.handle
+    }
+
+    fn from_x11_handle(connection: &'a X11Connection, handle: u32) -> Self {
+        Self {
+            connection,
+            handle,
+        }
+    }
+}
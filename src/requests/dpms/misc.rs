@@ -0,0 +1,42 @@
+use crate::{
+    coding::dpms::{GetVersionRequest, GetVersionResponse},
+    net::{ExtInfo, Extension},
+};
+
+use super::*;
+
+impl X11Connection {
+    pub(crate) async fn enable_dpms(&self) -> Result<()> {
+        // query_extension
+        let queried = self.query_extension(DPMS_EXT_NAME).await?;
+        ensure!(queried.present, "dpms missing on x11 server");
+        self.0.registered_extensions.insert(
+            DPMS_EXT_NAME.to_string(),
+            ExtInfo {
+                extension: Extension::Dpms,
+                major_opcode: queried.major_opcode,
+                event_start: queried.first_event,
+                error_start: queried.first_error,
+                event_count: DPMS_EVENT_COUNT,
+                version: None,
+            },
+        );
+
+        // enable extension
+        let reply = send_request_ext!(
+            self,
+            queried.major_opcode,
+            DpmsOpcode::GetVersion,
+            GetVersionResponse,
+            GetVersionRequest {
+                client_major_version: 1,
+                client_minor_version: 1,
+            }
+        );
+        if reply.server_major_version != 1 {
+            bail!("unsupported dpms version on server: {}.{}", reply.server_major_version, reply.server_minor_version);
+        }
+        self.set_ext_version(DPMS_EXT_NAME, reply.server_major_version, reply.server_minor_version);
+        Ok(())
+    }
+}
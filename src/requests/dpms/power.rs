@@ -0,0 +1,82 @@
+pub use crate::coding::dpms::DpmsLevel;
+use crate::coding::dpms::{
+    CapableRequest, CapableResponse, DisableRequest, EnableRequest, ForceLevelRequest, GetTimeoutsRequest, GetTimeoutsResponse, InfoRequest, InfoResponse,
+    SetTimeoutsRequest,
+};
+
+use super::*;
+
+/// Seconds of inactivity before the display enters each level, 0 if disabled
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DpmsTimeouts {
+    pub standby: u16,
+    pub suspend: u16,
+    pub off: u16,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DpmsInfo {
+    pub level: DpmsLevel,
+    pub enabled: bool,
+}
+
+impl X11Connection {
+    /// Whether the display supports power management
+    pub async fn dpms_capable(&self) -> Result<bool> {
+        let reply = send_request_dpms!(self, DpmsOpcode::Capable, CapableResponse, CapableRequest {});
+        Ok(reply.capable)
+    }
+
+    pub async fn dpms_get_timeouts(&self) -> Result<DpmsTimeouts> {
+        let reply = send_request_dpms!(self, DpmsOpcode::GetTimeouts, GetTimeoutsResponse, GetTimeoutsRequest {});
+        Ok(DpmsTimeouts {
+            standby: reply.standby_timeout,
+            suspend: reply.suspend_timeout,
+            off: reply.off_timeout,
+        })
+    }
+
+    /// Nonzero timeouts must not decrease from standby to suspend to off
+    pub async fn dpms_set_timeouts(&self, timeouts: DpmsTimeouts) -> Result<()> {
+        send_request_dpms!(
+            self,
+            DpmsOpcode::SetTimeouts,
+            SetTimeoutsRequest {
+                standby_timeout: timeouts.standby,
+                suspend_timeout: timeouts.suspend,
+                off_timeout: timeouts.off,
+            }
+        );
+        Ok(())
+    }
+
+    pub async fn dpms_enable(&self) -> Result<()> {
+        send_request_dpms!(self, DpmsOpcode::Enable, EnableRequest {});
+        Ok(())
+    }
+
+    pub async fn dpms_disable(&self) -> Result<()> {
+        send_request_dpms!(self, DpmsOpcode::Disable, DisableRequest {});
+        Ok(())
+    }
+
+    /// Puts the display into `level` immediately. Fails unless DPMS is enabled.
+    pub async fn dpms_force_level(&self, level: DpmsLevel) -> Result<()> {
+        send_request_dpms!(
+            self,
+            DpmsOpcode::ForceLevel,
+            ForceLevelRequest {
+                power_level: level,
+            }
+        );
+        Ok(())
+    }
+
+    pub async fn dpms_info(&self) -> Result<DpmsInfo> {
+        let reply = send_request_dpms!(self, DpmsOpcode::Info, InfoResponse, InfoRequest {});
+        Ok(DpmsInfo {
+            level: reply.power_level,
+            enabled: reply.state,
+        })
+    }
+}
@@ -0,0 +1,205 @@
+pub use crate::coding::dri2::{AttachFormat, Attachment, DRI2Buffer};
+use crate::coding::dri2::{
+    CopyRegionRequest, CopyRegionResponse, CreateDrawableRequest, DestroyDrawableRequest, GetBuffersRequest, GetBuffersResponse, GetBuffersWithFormatRequest,
+    GetBuffersWithFormatResponse, GetMSCRequest, GetMSCResponse, GetParamRequest, GetParamResponse, SwapBuffersRequest, SwapBuffersResponse,
+    SwapIntervalRequest, WaitMSCRequest, WaitMSCResponse, WaitSBCRequest, WaitSBCResponse,
+};
+
+use super::*;
+
+#[derive(Debug, Clone)]
+pub struct Dri2Buffers {
+    pub width: u32,
+    pub height: u32,
+    pub buffers: Vec<DRI2Buffer>,
+}
+
+/// Swap counters of a drawable
+#[derive(Debug, Clone, Copy)]
+pub struct Dri2Counters {
+    /// unadjusted system time, in microseconds
+    pub ust: u64,
+    /// media stream counter, incremented every vertical retrace
+    pub msc: u64,
+    /// swap buffer counter, incremented every completed swap
+    pub sbc: u64,
+}
+
+impl X11Connection {
+    pub async fn dri2_create_drawable(&self, drawable: impl Into<Drawable<'_>>) -> Result<()> {
+        send_request_dri2!(
+            self,
+            DRI2Opcode::CreateDrawable,
+            CreateDrawableRequest {
+                drawable: drawable.into().handle(),
+            }
+        );
+        Ok(())
+    }
+
+    pub async fn dri2_destroy_drawable(&self, drawable: impl Into<Drawable<'_>>) -> Result<()> {
+        send_request_dri2!(
+            self,
+            DRI2Opcode::DestroyDrawable,
+            DestroyDrawableRequest {
+                drawable: drawable.into().handle(),
+            }
+        );
+        Ok(())
+    }
+
+    pub async fn dri2_get_buffers(&self, drawable: impl Into<Drawable<'_>>, attachments: &[Attachment]) -> Result<Dri2Buffers> {
+        let reply = send_request_dri2!(
+            self,
+            DRI2Opcode::GetBuffers,
+            GetBuffersResponse,
+            GetBuffersRequest {
+                drawable: drawable.into().handle(),
+                attachments: attachments.to_vec(),
+            }
+        );
+
+        Ok(Dri2Buffers {
+            width: reply.width,
+            height: reply.height,
+            buffers: reply.buffers,
+        })
+    }
+
+    pub async fn dri2_get_buffers_with_format(&self, drawable: impl Into<Drawable<'_>>, attachments: &[AttachFormat]) -> Result<Dri2Buffers> {
+        let reply = send_request_dri2!(
+            self,
+            DRI2Opcode::GetBuffersWithFormat,
+            GetBuffersWithFormatResponse,
+            GetBuffersWithFormatRequest {
+                drawable: drawable.into().handle(),
+                attachments: attachments.to_vec(),
+            }
+        );
+
+        Ok(Dri2Buffers {
+            width: reply.width,
+            height: reply.height,
+            buffers: reply.buffers,
+        })
+    }
+
+    /// Copies `region` of the `src` buffer to the `dest` buffer of `drawable`
+    pub async fn dri2_copy_region(&self, drawable: impl Into<Drawable<'_>>, region: Region<'_>, dest: Attachment, src: Attachment) -> Result<()> {
+        send_request_dri2!(
+            self,
+            DRI2Opcode::CopyRegion,
+            CopyRegionResponse,
+            CopyRegionRequest {
+                drawable: drawable.into().handle(),
+                region: region.handle,
+                dest: dest as u32,
+                src: src as u32,
+            }
+        );
+        Ok(())
+    }
+
+    /// Schedules a swap at `target_msc`, or the next msc where `msc % divisor == remainder`, returning the msc it will happen at
+    pub async fn dri2_swap_buffers(&self, drawable: impl Into<Drawable<'_>>, target_msc: u64, divisor: u64, remainder: u64) -> Result<u64> {
+        let reply = send_request_dri2!(
+            self,
+            DRI2Opcode::SwapBuffers,
+            SwapBuffersResponse,
+            SwapBuffersRequest {
+                drawable: drawable.into().handle(),
+                target_msc: target_msc,
+                divisor: divisor,
+                remainder: remainder,
+            }
+        );
+        Ok(reply.swap)
+    }
+
+    pub async fn dri2_get_msc(&self, drawable: impl Into<Drawable<'_>>) -> Result<Dri2Counters> {
+        let reply = send_request_dri2!(
+            self,
+            DRI2Opcode::GetMSC,
+            GetMSCResponse,
+            GetMSCRequest {
+                drawable: drawable.into().handle(),
+            }
+        );
+
+        Ok(Dri2Counters {
+            ust: reply.ust,
+            msc: reply.msc,
+            sbc: reply.sbc,
+        })
+    }
+
+    /// Waits until `target_msc`, or the next msc where `msc % divisor == remainder`
+    pub async fn dri2_wait_msc(&self, drawable: impl Into<Drawable<'_>>, target_msc: u64, divisor: u64, remainder: u64) -> Result<Dri2Counters> {
+        let reply = send_request_dri2!(
+            self,
+            DRI2Opcode::WaitMSC,
+            WaitMSCResponse,
+            WaitMSCRequest {
+                drawable: drawable.into().handle(),
+                target_msc: target_msc,
+                divisor: divisor,
+                remainder: remainder,
+            }
+        );
+
+        Ok(Dri2Counters {
+            ust: reply.ust,
+            msc: reply.msc,
+            sbc: reply.sbc,
+        })
+    }
+
+    /// Waits until the swap counter reaches `target_sbc`, or until all pending swaps completed if it is 0
+    pub async fn dri2_wait_sbc(&self, drawable: impl Into<Drawable<'_>>, target_sbc: u64) -> Result<Dri2Counters> {
+        let reply = send_request_dri2!(
+            self,
+            DRI2Opcode::WaitSBC,
+            WaitSBCResponse,
+            WaitSBCRequest {
+                drawable: drawable.into().handle(),
+                target_sbc: target_sbc,
+            }
+        );
+
+        Ok(Dri2Counters {
+            ust: reply.ust,
+            msc: reply.msc,
+            sbc: reply.sbc,
+        })
+    }
+
+    pub async fn dri2_swap_interval(&self, drawable: impl Into<Drawable<'_>>, interval: u32) -> Result<()> {
+        send_request_dri2!(
+            self,
+            DRI2Opcode::SwapInterval,
+            SwapIntervalRequest {
+                drawable: drawable.into().handle(),
+                interval: interval,
+            }
+        );
+        Ok(())
+    }
+
+    /// Returns `None` if the server doesn't recognize `param`
+    pub async fn dri2_get_param(&self, drawable: impl Into<Drawable<'_>>, param: u32) -> Result<Option<u64>> {
+        let reply = send_request_dri2!(
+            self,
+            DRI2Opcode::GetParam,
+            GetParamResponse,
+            GetParamRequest {
+                drawable: drawable.into().handle(),
+                param: param,
+            }
+        );
+        let is_param_recognized = reply.reserved != 0;
+        if !is_param_recognized {
+            return Ok(None);
+        }
+        Ok(Some(reply.into_inner().value))
+    }
+}
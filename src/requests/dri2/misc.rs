@@ -0,0 +1,85 @@
+pub use crate::coding::dri2::DriverType;
+use crate::{
+    coding::dri2::{AuthenticateRequest, AuthenticateResponse, ConnectRequest, ConnectResponse, QueryVersionRequest, QueryVersionResponse},
+    net::{ExtInfo, Extension},
+};
+
+use super::*;
+
+#[derive(Debug, Clone)]
+pub struct Dri2Device {
+    pub driver_name: String,
+    /// path of the DRM device node
+    pub device_name: String,
+}
+
+impl X11Connection {
+    pub(crate) async fn enable_dri2(&self) -> Result<()> {
+        // query_extension
+        let queried = self.query_extension(DRI2_EXT_NAME).await?;
+        ensure!(queried.present, "dri2 missing on x11 server");
+        self.0.registered_extensions.insert(
+            DRI2_EXT_NAME.to_string(),
+            ExtInfo {
+                extension: Extension::DRI2,
+                major_opcode: queried.major_opcode,
+                event_start: queried.first_event,
+                error_start: queried.first_error,
+                event_count: DRI2_EVENT_COUNT,
+                version: None,
+            },
+        );
+
+        // enable extension
+        let reply = send_request_ext!(
+            self,
+            queried.major_opcode,
+            DRI2Opcode::QueryVersion,
+            QueryVersionResponse,
+            QueryVersionRequest {
+                major_version: 1,
+                minor_version: 4,
+            }
+        );
+        if reply.major_version != 1 {
+            bail!("unsupported dri2 version on server: {}.{}", reply.major_version, reply.minor_version);
+        }
+        self.set_ext_version(DRI2_EXT_NAME, reply.major_version, reply.minor_version);
+        Ok(())
+    }
+
+    /// Finds the driver used for `window`'s screen, returns `None` if the screen has no driver of that type
+    pub async fn dri2_connect(&self, window: Window<'_>, driver_type: DriverType) -> Result<Option<Dri2Device>> {
+        let reply = send_request_dri2!(
+            self,
+            DRI2Opcode::Connect,
+            ConnectResponse,
+            ConnectRequest {
+                window: window.handle,
+                driver_type: driver_type,
+            }
+        );
+        if reply.driver_name.is_empty() && reply.device_name.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Dri2Device {
+            driver_name: reply.driver_name,
+            device_name: reply.device_name,
+        }))
+    }
+
+    /// Authenticates a DRM `magic` token obtained from the device returned by [`X11Connection::dri2_connect`]
+    pub async fn dri2_authenticate(&self, window: Window<'_>, magic: u32) -> Result<bool> {
+        let reply = send_request_dri2!(
+            self,
+            DRI2Opcode::Authenticate,
+            AuthenticateResponse,
+            AuthenticateRequest {
+                window: window.handle,
+                magic: magic,
+            }
+        );
+        Ok(reply.authenticated != 0)
+    }
+}
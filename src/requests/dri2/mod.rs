@@ -0,0 +1,39 @@
+use super::*;
+use crate::coding::dri2::DRI2Opcode;
+use crate::coding::RequestBody;
+
+pub const DRI2_EXT_NAME: &str = "DRI2";
+const DRI2_EVENT_COUNT: u8 = 2;
+
+macro_rules! send_request_dri2 {
+    ($self_:expr, $opcode:expr, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let ext_code = $self_.0.registered_extensions.get(DRI2_EXT_NAME).unwrap().major_opcode;
+            send_request_ext!($self_, ext_code, $opcode, $name { $($key: $value,)* })
+        }
+    };
+    ($self_:expr, $opcode:expr, $reply:ident, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let ext_code = $self_.0.registered_extensions.get(DRI2_EXT_NAME).unwrap().major_opcode;
+            send_request_ext!($self_, ext_code, $opcode, $reply, $name { $($key: $value,)* })
+        }
+    };
+    ($self_:expr, $opcode:expr, parse_reserved $reply:ident, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let ext_code = $self_.0.registered_extensions.get(DRI2_EXT_NAME).unwrap().major_opcode;
+            send_request_ext!($self_, ext_code, $opcode, parse_reserved $reply, $name { $($key: $value,)* })
+        }
+    };
+    ($self_:expr, $opcode:expr, stream, $reply:ident, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let ext_code = $self_.0.registered_extensions.get(DRI2_EXT_NAME).unwrap().major_opcode;
+            send_request_ext!($self_, ext_code, $opcode, stream, $reply, $name { $($key: $value,)* })
+        }
+    };
+}
+
+mod misc;
+pub use misc::*;
+
+mod drawable;
+pub use drawable::*;
@@ -0,0 +1,197 @@
+use crate::{
+    coding::dri3::{
+        BufferFromPixmapRequest, BufferFromPixmapResponse, FenceFromFdRequest, GetSupportedModifiersRequest, GetSupportedModifiersResponse,
+        PixmapFromBufferRequest, PixmapFromBuffersRequest,
+    },
+    connection::OwnedFd,
+};
+
+use super::*;
+
+/// A single plane buffer shared with the server, such as a dma-buf
+#[derive(Debug)]
+pub struct Dri3Buffer {
+    pub fd: OwnedFd,
+    /// size of the buffer in bytes
+    pub size: u32,
+    pub width: u16,
+    pub height: u16,
+    /// bytes per scanline
+    pub stride: u16,
+    pub depth: u8,
+    /// bits per pixel
+    pub bpp: u8,
+}
+
+#[derive(Debug)]
+pub struct Dri3Plane {
+    pub fd: OwnedFd,
+    /// bytes per scanline
+    pub stride: u32,
+    /// offset of the plane in the buffer
+    pub offset: u32,
+}
+
+/// A buffer of up to 4 planes, laid out as described by its DRM format modifier
+#[derive(Debug)]
+pub struct Dri3Buffers {
+    pub planes: Vec<Dri3Plane>,
+    pub width: u16,
+    pub height: u16,
+    pub depth: u8,
+    /// bits per pixel
+    pub bpp: u8,
+    pub modifier: u64,
+}
+
+/// DRM format modifiers supported for a depth and bpp
+#[derive(Debug, Clone)]
+pub struct Dri3Modifiers {
+    /// modifiers usable for pixmaps presented to the window
+    pub window: Vec<u64>,
+    /// modifiers usable anywhere on the window's screen
+    pub screen: Vec<u64>,
+}
+
+impl<'a> Window<'a> {
+    /// Creates a pixmap on this window's screen backed by `buffer`
+    pub async fn dri3_pixmap_from_buffer(self, buffer: Dri3Buffer) -> Result<Pixmap<'a>> {
+        let pixmap = self.connection.new_resource_id();
+
+        send_request_dri3!(
+            self.connection,
+            DRI3Opcode::PixmapFromBuffer,
+            fds vec![buffer.fd],
+            PixmapFromBufferRequest {
+                pixmap: pixmap,
+                drawable: self.handle,
+                size: buffer.size,
+                width: buffer.width,
+                height: buffer.height,
+                stride: buffer.stride,
+                depth: buffer.depth,
+                bpp: buffer.bpp,
+            }
+        );
+
+        Ok(Pixmap {
+            handle: pixmap,
+            connection: self.connection,
+        })
+    }
+
+    /// Creates a pixmap on this window's screen backed by the planes of `buffers`, which requires dri3 1.2
+    pub async fn dri3_pixmap_from_buffers(self, buffers: Dri3Buffers) -> Result<Pixmap<'a>> {
+        self.connection.require_ext_version(DRI3_EXT_NAME, 1, 2, "PixmapFromBuffers")?;
+        if buffers.planes.is_empty() || buffers.planes.len() > 4 {
+            bail!("dri3 buffers must have between 1 and 4 planes, got {}", buffers.planes.len());
+        }
+        let pixmap = self.connection.new_resource_id();
+        let num_buffers = buffers.planes.len() as u8;
+        let mut layout = [(0u32, 0u32); 4];
+        for (i, plane) in buffers.planes.iter().enumerate() {
+            layout[i] = (plane.stride, plane.offset);
+        }
+        let fds = buffers.planes.into_iter().map(|x| x.fd).collect();
+
+        send_request_dri3!(
+            self.connection,
+            DRI3Opcode::PixmapFromBuffers,
+            fds fds,
+            PixmapFromBuffersRequest {
+                pixmap: pixmap,
+                window: self.handle,
+                num_buffers: num_buffers,
+                width: buffers.width,
+                height: buffers.height,
+                stride0: layout[0].0,
+                offset0: layout[0].1,
+                stride1: layout[1].0,
+                offset1: layout[1].1,
+                stride2: layout[2].0,
+                offset2: layout[2].1,
+                stride3: layout[3].0,
+                offset3: layout[3].1,
+                depth: buffers.depth,
+                bpp: buffers.bpp,
+                modifier: buffers.modifier,
+            }
+        );
+
+        Ok(Pixmap {
+            handle: pixmap,
+            connection: self.connection,
+        })
+    }
+
+    /// Creates a SYNC fence on this window's screen from a fence file descriptor, such as a shared memory fence
+    pub async fn dri3_fence_from_fd(self, fd: OwnedFd, triggered: bool) -> Result<Fence<'a>> {
+        let fence = self.connection.new_resource_id();
+
+        send_request_dri3!(
+            self.connection,
+            DRI3Opcode::FenceFromFd,
+            fds vec![fd],
+            FenceFromFdRequest {
+                drawable: self.handle,
+                fence: fence,
+                initially_triggered: triggered,
+            }
+        );
+
+        Ok(Fence {
+            handle: fence,
+            connection: self.connection,
+        })
+    }
+
+    /// DRM format modifiers supported for pixmaps of `depth` and `bpp`, which requires dri3 1.2
+    pub async fn dri3_supported_modifiers(self, depth: u8, bpp: u8) -> Result<Dri3Modifiers> {
+        self.connection.require_ext_version(DRI3_EXT_NAME, 1, 2, "GetSupportedModifiers")?;
+        let reply = send_request_dri3!(
+            self.connection,
+            DRI3Opcode::GetSupportedModifiers,
+            GetSupportedModifiersResponse,
+            GetSupportedModifiersRequest {
+                window: self.handle,
+                depth: depth,
+                bpp: bpp,
+            }
+        )
+        .into_inner();
+
+        Ok(Dri3Modifiers {
+            window: reply.window_modifiers,
+            screen: reply.screen_modifiers,
+        })
+    }
+}
+
+impl<'a> Pixmap<'a> {
+    /// Exports the buffer backing this pixmap. Requires a unix socket connection to receive the file descriptor.
+    pub async fn dri3_buffer(self) -> Result<Dri3Buffer> {
+        let (reply, fds) = send_request_dri3!(
+            self.connection,
+            DRI3Opcode::BufferFromPixmap,
+            receive_fds BufferFromPixmapResponse,
+            BufferFromPixmapRequest {
+                pixmap: self.handle,
+            }
+        );
+        let fd = match fds.into_iter().next() {
+            Some(fd) => fd,
+            None => bail!("x11 server did not send a file descriptor for the pixmap buffer"),
+        };
+        let reply = reply.into_inner();
+
+        Ok(Dri3Buffer {
+            fd,
+            size: reply.size,
+            width: reply.width,
+            height: reply.height,
+            stride: reply.stride,
+            depth: reply.depth,
+            bpp: reply.bpp,
+        })
+    }
+}
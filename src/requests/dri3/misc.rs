@@ -0,0 +1,63 @@
+use crate::{
+    coding::dri3::{OpenRequest, OpenResponse, QueryVersionRequest, QueryVersionResponse},
+    connection::OwnedFd,
+    net::{ExtInfo, Extension},
+};
+
+use super::*;
+
+impl X11Connection {
+    pub(crate) async fn enable_dri3(&self) -> Result<()> {
+        // query_extension
+        let queried = self.query_extension(DRI3_EXT_NAME).await?;
+        ensure!(queried.present, "dri3 missing on x11 server");
+        self.0.registered_extensions.insert(
+            DRI3_EXT_NAME.to_string(),
+            ExtInfo {
+                extension: Extension::DRI3,
+                major_opcode: queried.major_opcode,
+                event_start: queried.first_event,
+                error_start: queried.first_error,
+                event_count: DRI3_EVENT_COUNT,
+                version: None,
+            },
+        );
+
+        // enable extension
+        let reply = send_request_ext!(
+            self,
+            queried.major_opcode,
+            DRI3Opcode::QueryVersion,
+            QueryVersionResponse,
+            QueryVersionRequest {
+                major_version: 1,
+                minor_version: 2,
+            }
+        );
+        if reply.major_version != 1 {
+            bail!("unsupported dri3 version on server: {}.{}", reply.major_version, reply.minor_version);
+        }
+        self.set_ext_version(DRI3_EXT_NAME, reply.major_version, reply.minor_version);
+        Ok(())
+    }
+}
+
+impl<'a> Window<'a> {
+    /// Opens the DRM device rendering to this window's screen, through `provider` or the screen's default provider.
+    /// Requires a unix socket connection to receive the file descriptor.
+    pub async fn dri3_open(self, provider: Option<Provider<'_>>) -> Result<OwnedFd> {
+        let (_, fds) = send_request_dri3!(
+            self.connection,
+            DRI3Opcode::Open,
+            receive_fds OpenResponse,
+            OpenRequest {
+                drawable: self.handle,
+                provider: provider.map(|x| x.handle).unwrap_or(0),
+            }
+        );
+        match fds.into_iter().next() {
+            Some(fd) => Ok(fd),
+            None => bail!("x11 server did not send a file descriptor for the dri3 device"),
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use super::*;
+use crate::coding::dri3::DRI3Opcode;
+use crate::coding::RequestBody;
+
+pub const DRI3_EXT_NAME: &str = "DRI3";
+const DRI3_EVENT_COUNT: u8 = 0;
+
+macro_rules! send_request_dri3 {
+    ($self_:expr, $opcode:expr, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let ext_code = $self_.0.registered_extensions.get(DRI3_EXT_NAME).unwrap().major_opcode;
+            send_request_ext!($self_, ext_code, $opcode, $name { $($key: $value,)* })
+        }
+    };
+    ($self_:expr, $opcode:expr, $reply:ident, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let ext_code = $self_.0.registered_extensions.get(DRI3_EXT_NAME).unwrap().major_opcode;
+            send_request_ext!($self_, ext_code, $opcode, $reply, $name { $($key: $value,)* })
+        }
+    };
+    ($self_:expr, $opcode:expr, parse_reserved $reply:ident, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let ext_code = $self_.0.registered_extensions.get(DRI3_EXT_NAME).unwrap().major_opcode;
+            send_request_ext!($self_, ext_code, $opcode, parse_reserved $reply, $name { $($key: $value,)* })
+        }
+    };
+    ($self_:expr, $opcode:expr, fds $fds:expr, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let ext_code = $self_.0.registered_extensions.get(DRI3_EXT_NAME).unwrap().major_opcode;
+            send_request_ext!($self_, ext_code, $opcode, fds $fds, $name { $($key: $value,)* })
+        }
+    };
+    ($self_:expr, $opcode:expr, receive_fds $reply:ident, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let ext_code = $self_.0.registered_extensions.get(DRI3_EXT_NAME).unwrap().major_opcode;
+            send_request_ext!($self_, ext_code, $opcode, receive_fds $reply, $name { $($key: $value,)* })
+        }
+    };
+    ($self_:expr, $opcode:expr, stream, $reply:ident, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let ext_code = $self_.0.registered_extensions.get(DRI3_EXT_NAME).unwrap().major_opcode;
+            send_request_ext!($self_, ext_code, $opcode, stream, $reply, $name { $($key: $value,)* })
+        }
+    };
+}
+
+mod misc;
+pub use misc::*;
+
+mod buffer;
+pub use buffer::*;
@@ -71,3 +71,47 @@ impl Into<crate::coding::shape::Rectangle> for Rectangle {
         }
     }
 }
+
+impl From<crate::coding::render::Rectangle> for Rectangle {
+    fn from(from: crate::coding::render::Rectangle) -> Self {
+        Self {
+            x: from.x,
+            y: from.y,
+            width: from.width,
+            height: from.height,
+        }
+    }
+}
+
+impl Into<crate::coding::render::Rectangle> for Rectangle {
+    fn into(self) -> crate::coding::render::Rectangle {
+        crate::coding::render::Rectangle {
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl From<crate::coding::damage::Rectangle> for Rectangle {
+    fn from(from: crate::coding::damage::Rectangle) -> Self {
+        Self {
+            x: from.x,
+            y: from.y,
+            width: from.width,
+            height: from.height,
+        }
+    }
+}
+
+impl Into<crate::coding::damage::Rectangle> for Rectangle {
+    fn into(self) -> crate::coding::damage::Rectangle {
+        crate::coding::damage::Rectangle {
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
@@ -24,9 +24,51 @@ pub use shape::*;
 pub mod xge;
 pub use xge::*;
 
+pub mod bigreq;
+pub use bigreq::*;
+
 pub mod xrecord;
 pub use xrecord::*;
 
+pub mod render;
+pub use render::*;
+
+pub mod shm;
+pub use shm::*;
+
+pub mod dpms;
+pub use dpms::*;
+
+pub mod dri2;
+pub use dri2::*;
+
+pub mod dri3;
+pub use dri3::*;
+
+pub mod composite;
+pub use composite::*;
+
+pub mod damage;
+pub use damage::*;
+
+pub mod present;
+pub use present::*;
+
+pub mod sync;
+pub use sync::*;
+
+pub mod xtest;
+pub use xtest::*;
+
+pub mod xinerama;
+pub use xinerama::*;
+
+pub mod screensaver;
+pub use screensaver::*;
+
+pub mod xres;
+pub use xres::*;
+
 mod misc;
 pub use misc::*;
 
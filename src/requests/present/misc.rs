@@ -0,0 +1,68 @@
+pub use crate::coding::present::PresentCapabilities;
+use crate::{
+    coding::present::{QueryCapabilitiesRequest, QueryCapabilitiesResponse, QueryVersionRequest, QueryVersionResponse},
+    net::{ExtInfo, Extension},
+};
+
+use super::*;
+
+impl X11Connection {
+    pub(crate) async fn enable_present(&self) -> Result<()> {
+        // query_extension
+        let queried = self.query_extension(PRESENT_EXT_NAME).await?;
+        ensure!(queried.present, "present missing on x11 server");
+        self.0.registered_extensions.insert(
+            PRESENT_EXT_NAME.to_string(),
+            ExtInfo {
+                extension: Extension::Present,
+                major_opcode: queried.major_opcode,
+                event_start: queried.first_event,
+                error_start: queried.first_error,
+                event_count: PRESENT_EVENT_COUNT,
+                version: None,
+            },
+        );
+
+        // enable extension
+        let reply = send_request_ext!(
+            self,
+            queried.major_opcode,
+            PresentOpcode::QueryVersion,
+            QueryVersionResponse,
+            QueryVersionRequest {
+                major_version: 1,
+                minor_version: 2,
+            }
+        );
+        if reply.major_version != 1 {
+            bail!("unsupported present version on server: {}.{}", reply.major_version, reply.minor_version);
+        }
+        self.set_ext_version(PRESENT_EXT_NAME, reply.major_version, reply.minor_version);
+        Ok(())
+    }
+
+    async fn present_query_capabilities(&self, target: u32) -> Result<PresentCapabilities> {
+        let reply = send_request_present!(
+            self,
+            PresentOpcode::QueryCapabilities,
+            QueryCapabilitiesResponse,
+            QueryCapabilitiesRequest {
+                target: target,
+            }
+        );
+        Ok(reply.capabilities)
+    }
+}
+
+impl<'a> Window<'a> {
+    /// Presentation features available for this window's screen
+    pub async fn present_capabilities(self) -> Result<PresentCapabilities> {
+        self.connection.present_query_capabilities(self.handle).await
+    }
+}
+
+impl<'a> Crtc<'a> {
+    pub async fn present_capabilities(self) -> Result<PresentCapabilities> {
+        self.connection.present_query_capabilities(self.handle).await
+    }
+}
@@ -0,0 +1,132 @@
+use crate::coding::present::{Notify, NotifyMSCRequest, PixmapRequest, SelectInputRequest};
+pub use crate::coding::present::{PresentEventMask, PresentOptions};
+use derive_builder::Builder;
+
+use super::*;
+
+/// Another window to send a CompleteNotify to once a presentation completes
+#[derive(Debug, Clone, Copy)]
+pub struct PresentNotify<'a> {
+    pub window: Window<'a>,
+    pub serial: u32,
+}
+
+#[derive(Default, Builder, Debug)]
+#[builder(default)]
+pub struct PresentPixmapParams<'a> {
+    /// identifies the presentation in the resulting events
+    pub serial: u32,
+    /// part of the pixmap with valid contents, the whole pixmap if `None`
+    #[builder(setter(into, strip_option), default)]
+    pub valid: Option<Region<'a>>,
+    /// part of the window to update, the whole pixmap if `None`
+    #[builder(setter(into, strip_option), default)]
+    pub update: Option<Region<'a>>,
+    pub x_off: i16,
+    pub y_off: i16,
+    /// crtc to synchronize with, chosen by the server if `None`
+    #[builder(setter(into, strip_option), default)]
+    pub target_crtc: Option<Crtc<'a>>,
+    /// fence to wait for before presenting
+    #[builder(setter(into, strip_option), default)]
+    pub wait_fence: Option<Fence<'a>>,
+    /// fence triggered once the pixmap is idle
+    #[builder(setter(into, strip_option), default)]
+    pub idle_fence: Option<Fence<'a>>,
+    pub options: PresentOptions,
+    pub target_msc: u64,
+    pub divisor: u64,
+    pub remainder: u64,
+    pub notifies: Vec<PresentNotify<'a>>,
+}
+
+/// An event selection made with [`Window::present_select_input`], identifying the events it produces
+#[derive(Clone, Copy, derivative::Derivative)]
+#[derivative(Debug)]
+pub struct PresentEventSelection<'a> {
+    pub(crate) handle: u32,
+    pub window: Window<'a>,
+    #[derivative(Debug = "ignore")]
+    pub(crate) connection: &'a X11Connection,
+}
+
+impl<'a> Window<'a> {
+    /// Presents `pixmap` in this window at `target_msc`, or the next msc where `msc % divisor == remainder`
+    pub async fn present_pixmap(self, pixmap: Pixmap<'_>, params: PresentPixmapParams<'_>) -> Result<()> {
+        send_request_present!(
+            self.connection,
+            PresentOpcode::Pixmap,
+            PixmapRequest {
+                window: self.handle,
+                pixmap: pixmap.handle,
+                serial: params.serial,
+                valid: params.valid.map(|x| x.handle).unwrap_or(0),
+                update: params.update.map(|x| x.handle).unwrap_or(0),
+                x_off: params.x_off,
+                y_off: params.y_off,
+                target_crtc: params.target_crtc.map(|x| x.handle).unwrap_or(0),
+                wait_fence: params.wait_fence.map(|x| x.handle).unwrap_or(0),
+                idle_fence: params.idle_fence.map(|x| x.handle).unwrap_or(0),
+                options: params.options,
+                target_msc: params.target_msc,
+                divisor: params.divisor,
+                remainder: params.remainder,
+                notifies: params
+                    .notifies
+                    .into_iter()
+                    .map(|x| Notify {
+                        window: x.window.handle,
+                        serial: x.serial,
+                    })
+                    .collect(),
+            }
+        );
+        Ok(())
+    }
+
+    /// Requests a CompleteNotify at `target_msc`, or the next msc where `msc % divisor == remainder`
+    pub async fn present_notify_msc(self, serial: u32, target_msc: u64, divisor: u64, remainder: u64) -> Result<()> {
+        send_request_present!(
+            self.connection,
+            PresentOpcode::NotifyMSC,
+            NotifyMSCRequest {
+                window: self.handle,
+                serial: serial,
+                target_msc: target_msc,
+                divisor: divisor,
+                remainder: remainder,
+            }
+        );
+        Ok(())
+    }
+
+    pub async fn present_select_input(self, event_mask: PresentEventMask) -> Result<PresentEventSelection<'a>> {
+        let selection = PresentEventSelection {
+            handle: self.connection.new_resource_id(),
+            window: self,
+            connection: self.connection,
+        };
+        selection.update(event_mask).await?;
+        Ok(selection)
+    }
+}
+
+impl<'a> PresentEventSelection<'a> {
+    /// Changes the selected events, selecting no events frees the selection
+    pub async fn update(self, event_mask: PresentEventMask) -> Result<()> {
+        send_request_present!(
+            self.connection,
+            PresentOpcode::SelectInput,
+            SelectInputRequest {
+                event_id: self.handle,
+                window: self.window.handle,
+                event_mask: event_mask,
+            }
+        );
+        Ok(())
+    }
+
+    pub async fn free(self) -> Result<()> {
+        self.update(PresentEventMask::ZERO).await
+    }
+}
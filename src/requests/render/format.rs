@@ -0,0 +1,182 @@
+pub use crate::coding::render::{DirectFormat, IndexValue, PictType};
+use crate::coding::render::{QueryPictFormatsRequest, QueryPictFormatsResponse, QueryPictIndexValuesRequest, QueryPictIndexValuesResponse};
+
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PictFormat {
+    pub(crate) handle: u32,
+}
+
+impl<'a> Resource<'a> for PictFormat {
+    fn x11_handle(&self) -> u32 {
+        self.handle
+    }
+
+    fn from_x11_handle(_connection: &'a X11Connection, handle: u32) -> Self {
+        Self {
+            handle,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PictFormatInfo<'a> {
+    pub format: PictFormat,
+    pub type_: PictType,
+    pub depth: u8,
+    pub direct: DirectFormat,
+    pub colormap: Option<Colormap<'a>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PictDepth {
+    pub depth: u8,
+    pub visuals: Vec<(Visual, PictFormat)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PictScreen {
+    pub fallback: PictFormat,
+    pub depths: Vec<PictDepth>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PictFormats<'a> {
+    pub formats: Vec<PictFormatInfo<'a>>,
+    /// in the same order as `X11Connection::screens`
+    pub screens: Vec<PictScreen>,
+    /// subpixel order of each screen, empty for servers before render 0.6
+    pub subpixels: Vec<SubPixel>,
+}
+
+/// The formats every render server provides, like `XRenderFindStandardFormat`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StandardFormat {
+    Argb32,
+    Rgb24,
+    A8,
+    A4,
+    A1,
+}
+
+impl StandardFormat {
+    // depth, (shift, mask) for alpha, red, green, blue
+    fn layout(self) -> (u8, [(u16, u16); 4]) {
+        match self {
+            StandardFormat::Argb32 => (32, [(24, 0xff), (16, 0xff), (8, 0xff), (0, 0xff)]),
+            StandardFormat::Rgb24 => (24, [(0, 0), (16, 0xff), (8, 0xff), (0, 0xff)]),
+            StandardFormat::A8 => (8, [(0, 0xff), (0, 0), (0, 0), (0, 0)]),
+            StandardFormat::A4 => (4, [(0, 0xf), (0, 0), (0, 0), (0, 0)]),
+            StandardFormat::A1 => (1, [(0, 0x1), (0, 0), (0, 0), (0, 0)]),
+        }
+    }
+}
+
+impl<'a> PictFormats<'a> {
+    pub fn get(&self, format: PictFormat) -> Option<&PictFormatInfo<'a>> {
+        self.formats.iter().find(|x| x.format == format)
+    }
+
+    pub fn find_standard(&self, standard: StandardFormat) -> Option<&PictFormatInfo<'a>> {
+        let (depth, [alpha, red, green, blue]) = standard.layout();
+        self.formats.iter().find(|x| {
+            let direct = &x.direct;
+            // masks of zero don't have a meaningful shift
+            let matches = |(shift, mask): (u16, u16), (actual_shift, actual_mask): (u16, u16)| mask == actual_mask && (mask == 0 || shift == actual_shift);
+            x.type_ == PictType::Direct
+                && x.depth == depth
+                && matches(alpha, (direct.alpha_shift, direct.alpha_mask))
+                && matches(red, (direct.red_shift, direct.red_mask))
+                && matches(green, (direct.green_shift, direct.green_mask))
+                && matches(blue, (direct.blue_shift, direct.blue_mask))
+        })
+    }
+
+    /// The format of pictures created for windows or pixmaps with `visual`
+    pub fn find_visual(&self, visual: Visual) -> Option<&PictFormatInfo<'a>> {
+        let format = self
+            .screens
+            .iter()
+            .flat_map(|screen| screen.depths.iter())
+            .flat_map(|depth| depth.visuals.iter())
+            .find(|(x, _)| x.handle == visual.handle)?
+            .1;
+        self.get(format)
+    }
+}
+
+impl X11Connection {
+    pub async fn query_pict_formats(&self) -> Result<PictFormats<'_>> {
+        let reply = send_request_render!(self, RenderOpcode::QueryPictFormats, QueryPictFormatsResponse, QueryPictFormatsRequest {}).into_inner();
+
+        Ok(PictFormats {
+            formats: reply
+                .formats
+                .into_iter()
+                .map(|format| PictFormatInfo {
+                    format: PictFormat {
+                        handle: format.pict_format,
+                    },
+                    type_: format.type_,
+                    depth: format.depth,
+                    direct: format.direct_format,
+                    colormap: match format.colormap {
+                        0 => None,
+                        handle => Some(Colormap {
+                            handle,
+                            connection: self,
+                        }),
+                    },
+                })
+                .collect(),
+            screens: reply
+                .screens
+                .into_iter()
+                .map(|screen| PictScreen {
+                    fallback: PictFormat {
+                        handle: screen.fallback,
+                    },
+                    depths: screen
+                        .pict_depths
+                        .into_iter()
+                        .map(|depth| PictDepth {
+                            depth: depth.depth,
+                            visuals: depth
+                                .pict_visuals
+                                .into_iter()
+                                .map(|x| {
+                                    (
+                                        Visual {
+                                            handle: x.visual,
+                                        },
+                                        PictFormat {
+                                            handle: x.pict_format,
+                                        },
+                                    )
+                                })
+                                .collect(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            // same values as the xrandr subpixel order
+            subpixels: reply.subpixels.into_iter().map(|x| SubPixel::from_repr(x as u8)).collect::<Result<_>>()?,
+        })
+    }
+
+    /// The colormap entries of an indexed format
+    pub async fn query_pict_index_values(&self, format: PictFormat) -> Result<Vec<IndexValue>> {
+        let reply = send_request_render!(
+            self,
+            RenderOpcode::QueryPictIndexValues,
+            QueryPictIndexValuesResponse,
+            QueryPictIndexValuesRequest {
+                pict_format: format.handle,
+            }
+        )
+        .into_inner();
+
+        Ok(reply.values)
+    }
+}
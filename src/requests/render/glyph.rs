@@ -0,0 +1,249 @@
+pub use crate::coding::render::GlyphInfo;
+use crate::coding::render::{
+    AddGlyphsRequest, CompositeGlyphs16Request, CompositeGlyphs32Request, CompositeGlyphs8Request, CreateGlyphSetRequest, FreeGlyphSetRequest,
+    FreeGlyphsRequest, GlyphCmd16, GlyphCmd32, GlyphCmd8, ReferenceGlyphSetRequest,
+};
+
+use super::*;
+
+#[derive(Clone, Copy, derivative::Derivative)]
+#[derivative(Debug)]
+pub struct GlyphSet<'a> {
+    pub(crate) handle: u32,
+    #[derivative(Debug = "ignore")]
+    pub(crate) connection: &'a X11Connection,
+}
+
+#[derive(Debug, Clone)]
+pub struct Glyph {
+    pub id: u32,
+    pub info: GlyphInfo,
+    /// image in the glyph set's format, with scanlines padded to 4 bytes
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub enum GlyphItem<'a> {
+    /// glyphs drawn after moving the pen by (dx, dy)
+    Glyphs(i16, i16, Vec<u32>),
+    /// switches the glyph set used by following items
+    GlyphSet(GlyphSet<'a>),
+}
+
+impl X11Connection {
+    pub async fn create_glyph_set(&self, format: PictFormat) -> Result<GlyphSet<'_>> {
+        let glyphset = self.new_resource_id();
+
+        send_request_render!(
+            self,
+            RenderOpcode::CreateGlyphSet,
+            CreateGlyphSetRequest {
+                glyphset: glyphset,
+                pict_format: format.handle,
+            }
+        );
+        Ok(GlyphSet {
+            handle: glyphset,
+            connection: self,
+        })
+    }
+}
+
+impl<'a> GlyphSet<'a> {
+    /// Creates another id for the same glyph set, which is freed once all ids are freed
+    pub async fn reference(self) -> Result<GlyphSet<'a>> {
+        let glyphset = self.connection.new_resource_id();
+
+        send_request_render!(
+            self.connection,
+            RenderOpcode::ReferenceGlyphSet,
+            ReferenceGlyphSetRequest {
+                glyphset: glyphset,
+                existing_glyphset: self.handle,
+            }
+        );
+        Ok(GlyphSet {
+            handle: glyphset,
+            connection: self.connection,
+        })
+    }
+
+    pub async fn free(self) -> Result<()> {
+        send_request_render!(
+            self.connection,
+            RenderOpcode::FreeGlyphSet,
+            FreeGlyphSetRequest {
+                glyphset: self.handle,
+            }
+        );
+        Ok(())
+    }
+
+    pub async fn add_glyphs(self, glyphs: Vec<Glyph>) -> Result<()> {
+        let mut data = vec![];
+        for glyph in &glyphs {
+            data.extend_from_slice(&glyph.data[..]);
+            // each image starts on a 4 byte boundary
+            data.resize((data.len() + 3) & !3, 0);
+        }
+
+        send_request_render!(
+            self.connection,
+            RenderOpcode::AddGlyphs,
+            AddGlyphsRequest {
+                glyphset: self.handle,
+                glyph_ids: glyphs.iter().map(|x| x.id).collect(),
+                glyph_infos: glyphs.into_iter().map(|x| x.info).collect(),
+                data: data,
+            }
+        );
+        Ok(())
+    }
+
+    pub async fn free_glyphs(self, glyphs: Vec<u32>) -> Result<()> {
+        send_request_render!(
+            self.connection,
+            RenderOpcode::FreeGlyphs,
+            FreeGlyphsRequest {
+                glyphset: self.handle,
+                glyph: glyphs,
+            }
+        );
+        Ok(())
+    }
+}
+
+impl<'a> Picture<'a> {
+    /// Composites glyphs from `glyphset` onto this picture, starting at the pen position `src_x`, `src_y`.
+    /// The narrowest request that fits every glyph id is used.
+    pub async fn composite_glyphs(
+        self,
+        op: PictOp,
+        src: Picture<'_>,
+        mask_format: Option<PictFormat>,
+        glyphset: GlyphSet<'_>,
+        src_x: i16,
+        src_y: i16,
+        items: Vec<GlyphItem<'_>>,
+    ) -> Result<()> {
+        let mut max_glyph = 0u32;
+        for item in &items {
+            if let GlyphItem::Glyphs(_, _, glyphs) = item {
+                if glyphs.len() > 254 {
+                    bail!("glyph runs cannot be >254 glyphs long");
+                }
+                max_glyph = glyphs.iter().copied().fold(max_glyph, u32::max);
+            }
+        }
+        let mask_pict_format = mask_format.map(|x| x.handle).unwrap_or(0);
+
+        if max_glyph <= u8::MAX as u32 {
+            send_request_render!(
+                self.connection,
+                RenderOpcode::CompositeGlyphs8,
+                CompositeGlyphs8Request {
+                    op: op,
+                    src_picture: src.handle,
+                    dst_picture: self.handle,
+                    mask_pict_format: mask_pict_format,
+                    glyphset: glyphset.handle,
+                    src_x: src_x,
+                    src_y: src_y,
+                    glyph_cmds: items
+                        .into_iter()
+                        .map(|item| match item {
+                            GlyphItem::Glyphs(dx, dy, glyphs) => GlyphCmd8 {
+                                len: glyphs.len() as u8,
+                                dx: dx,
+                                dy: dy,
+                                glyphset: None,
+                                chars: Some(glyphs.into_iter().map(|x| x as u8).collect()),
+                            },
+                            GlyphItem::GlyphSet(glyphset) => GlyphCmd8 {
+                                len: 255,
+                                glyphset: Some(glyphset.handle),
+                                ..Default::default()
+                            },
+                        })
+                        .collect(),
+                }
+            );
+        } else if max_glyph <= u16::MAX as u32 {
+            send_request_render!(
+                self.connection,
+                RenderOpcode::CompositeGlyphs16,
+                CompositeGlyphs16Request {
+                    op: op,
+                    src_picture: src.handle,
+                    dst_picture: self.handle,
+                    mask_pict_format: mask_pict_format,
+                    glyphset: glyphset.handle,
+                    src_x: src_x,
+                    src_y: src_y,
+                    glyph_cmds: items
+                        .into_iter()
+                        .map(|item| match item {
+                            GlyphItem::Glyphs(dx, dy, glyphs) => GlyphCmd16 {
+                                len: glyphs.len() as u8,
+                                dx: dx,
+                                dy: dy,
+                                glyphset: None,
+                                chars: Some(glyphs.into_iter().map(|x| x as u16).collect()),
+                            },
+                            GlyphItem::GlyphSet(glyphset) => GlyphCmd16 {
+                                len: 255,
+                                glyphset: Some(glyphset.handle),
+                                ..Default::default()
+                            },
+                        })
+                        .collect(),
+                }
+            );
+        } else {
+            send_request_render!(
+                self.connection,
+                RenderOpcode::CompositeGlyphs32,
+                CompositeGlyphs32Request {
+                    op: op,
+                    src_picture: src.handle,
+                    dst_picture: self.handle,
+                    mask_pict_format: mask_pict_format,
+                    glyphset: glyphset.handle,
+                    src_x: src_x,
+                    src_y: src_y,
+                    glyph_cmds: items
+                        .into_iter()
+                        .map(|item| match item {
+                            GlyphItem::Glyphs(dx, dy, glyphs) => GlyphCmd32 {
+                                len: glyphs.len() as u8,
+                                dx: dx,
+                                dy: dy,
+                                glyphset: None,
+                                chars: Some(glyphs),
+                            },
+                            GlyphItem::GlyphSet(glyphset) => GlyphCmd32 {
+                                len: 255,
+                                glyphset: Some(glyphset.handle),
+                                ..Default::default()
+                            },
+                        })
+                        .collect(),
+                }
+            );
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Resource<'a> for GlyphSet<'a> {
+    fn x11_handle(&self) -> u32 {
+        self.handle
+    }
+
+    fn from_x11_handle(connection: &'a X11Connection, handle: u32) -> Self {
+        Self {
+            connection,
+            handle,
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use crate::coding::render::{CreateConicalGradientRequest, CreateLinearGradientRequest, CreateRadialGradientRequest, CreateSolidFillRequest};
+
+use super::*;
+
+/// A color stop of a gradient, at an offset from 0 to 1 along it
+#[derive(Debug, Clone)]
+pub struct GradientStop {
+    pub offset: I16F16,
+    pub color: RenderColor,
+}
+
+impl X11Connection {
+    /// Creates a source-only picture of a single color
+    pub async fn create_solid_fill(&self, color: RenderColor) -> Result<Picture<'_>> {
+        let picture = self.new_resource_id();
+
+        send_request_render!(
+            self,
+            RenderOpcode::CreateSolidFill,
+            CreateSolidFillRequest {
+                picture: picture,
+                color: color,
+            }
+        );
+        Ok(Picture {
+            handle: picture,
+            connection: self,
+        })
+    }
+
+    pub async fn create_linear_gradient(&self, p1: PointFixed, p2: PointFixed, stops: &[GradientStop]) -> Result<Picture<'_>> {
+        let picture = self.new_resource_id();
+
+        send_request_render!(
+            self,
+            RenderOpcode::CreateLinearGradient,
+            CreateLinearGradientRequest {
+                picture: picture,
+                p1: p1.into(),
+                p2: p2.into(),
+                stops: stops.iter().map(|x| x.offset.into()).collect(),
+                colors: stops.iter().map(|x| x.color.clone()).collect(),
+            }
+        );
+        Ok(Picture {
+            handle: picture,
+            connection: self,
+        })
+    }
+
+    pub async fn create_radial_gradient(
+        &self,
+        inner: PointFixed,
+        outer: PointFixed,
+        inner_radius: I16F16,
+        outer_radius: I16F16,
+        stops: &[GradientStop],
+    ) -> Result<Picture<'_>> {
+        let picture = self.new_resource_id();
+
+        send_request_render!(
+            self,
+            RenderOpcode::CreateRadialGradient,
+            CreateRadialGradientRequest {
+                picture: picture,
+                inner: inner.into(),
+                outer: outer.into(),
+                inner_radius: inner_radius.into(),
+                outer_radius: outer_radius.into(),
+                stops: stops.iter().map(|x| x.offset.into()).collect(),
+                colors: stops.iter().map(|x| x.color.clone()).collect(),
+            }
+        );
+        Ok(Picture {
+            handle: picture,
+            connection: self,
+        })
+    }
+
+    /// `angle` is in degrees
+    pub async fn create_conical_gradient(&self, center: PointFixed, angle: I16F16, stops: &[GradientStop]) -> Result<Picture<'_>> {
+        let picture = self.new_resource_id();
+
+        send_request_render!(
+            self,
+            RenderOpcode::CreateConicalGradient,
+            CreateConicalGradientRequest {
+                picture: picture,
+                center: center.into(),
+                angle: angle.into(),
+                stops: stops.iter().map(|x| x.offset.into()).collect(),
+                colors: stops.iter().map(|x| x.color.clone()).collect(),
+            }
+        );
+        Ok(Picture {
+            handle: picture,
+            connection: self,
+        })
+    }
+}
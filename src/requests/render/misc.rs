@@ -0,0 +1,42 @@
+use crate::{
+    coding::render::{QueryVersionRequest, QueryVersionResponse},
+    net::{ExtInfo, Extension},
+};
+
+use super::*;
+
+impl X11Connection {
+    pub(crate) async fn enable_render(&self) -> Result<()> {
+        // query_extension
+        let queried = self.query_extension(RENDER_EXT_NAME).await?;
+        ensure!(queried.present, "render missing on x11 server");
+        self.0.registered_extensions.insert(
+            RENDER_EXT_NAME.to_string(),
+            ExtInfo {
+                extension: Extension::Render,
+                major_opcode: queried.major_opcode,
+                event_start: queried.first_event,
+                error_start: queried.first_error,
+                event_count: RENDER_EVENT_COUNT,
+                version: None,
+            },
+        );
+
+        // enable extension
+        let reply = send_request_ext!(
+            self,
+            queried.major_opcode,
+            RenderOpcode::QueryVersion,
+            QueryVersionResponse,
+            QueryVersionRequest {
+                client_major_version: 0,
+                client_minor_version: 11,
+            }
+        );
+        if reply.major_version != 0 || reply.minor_version < 10 {
+            bail!("unsupported render version on server: {}.{}", reply.major_version, reply.minor_version);
+        }
+        self.set_ext_version(RENDER_EXT_NAME, reply.major_version, reply.minor_version);
+        Ok(())
+    }
+}
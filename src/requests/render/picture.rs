@@ -0,0 +1,546 @@
+use derive_builder::Builder;
+
+use crate::coding::render::{
+    self, AddTrapsRequest, AnimCursorElt, ChangePictureRequest, CompositeRequest, CreateAnimCursorRequest, CreateCursorRequest, CreatePictureData,
+    CreatePictureRequest, FillRectanglesRequest, FreePictureRequest, LineFix, PointFix, QueryFiltersRequest, QueryFiltersResponse,
+    SetPictureClipRectanglesRequest, SetPictureFilterRequest, SetPictureTransformRequest, SpanFix, Transform, TrapezoidFix, TrapezoidsRequest, TriFanRequest,
+    TriStripRequest, TriangleFix, TrianglesRequest,
+};
+pub use crate::coding::render::{Color as RenderColor, CreatePictureMask, PictOp, PolyEdge, PolyMode, Repeat};
+
+use super::*;
+
+#[derive(Clone, Copy, derivative::Derivative)]
+#[derivative(Debug)]
+pub struct Picture<'a> {
+    pub(crate) handle: u32,
+    #[derivative(Debug = "ignore")]
+    pub(crate) connection: &'a X11Connection,
+}
+
+#[derive(Builder, Debug)]
+#[builder(default)]
+pub struct PictureParams<'a> {
+    pub repeat: Repeat,
+    #[builder(setter(into, strip_option), default)]
+    pub alpha_map: Option<Picture<'a>>,
+    pub alpha_x_origin: i16,
+    pub alpha_y_origin: i16,
+    pub clip_x_origin: i16,
+    pub clip_y_origin: i16,
+    #[builder(setter(into, strip_option), default)]
+    pub clip_mask: Option<Pixmap<'a>>,
+    pub graphics_exposures: bool,
+    pub subwindow_mode: SubwindowMode,
+    pub poly_edge: PolyEdge,
+    pub poly_mode: PolyMode,
+    #[builder(setter(into, strip_option), default)]
+    pub dither: Option<Atom>,
+    pub component_alpha: bool,
+}
+
+impl<'a> Default for PictureParams<'a> {
+    fn default() -> Self {
+        Self {
+            repeat: Repeat::None,
+            alpha_map: None,
+            alpha_x_origin: 0,
+            alpha_y_origin: 0,
+            clip_x_origin: 0,
+            clip_y_origin: 0,
+            clip_mask: None,
+            graphics_exposures: true,
+            subwindow_mode: SubwindowMode::ClipByChildren,
+            poly_edge: PolyEdge::Smooth,
+            poly_mode: PolyMode::Precise,
+            dither: None,
+            component_alpha: false,
+        }
+    }
+}
+
+impl<'a> Into<(CreatePictureMask, CreatePictureData)> for PictureParams<'a> {
+    fn into(self) -> (CreatePictureMask, CreatePictureData) {
+        let mut mask = CreatePictureMask::ZERO;
+        let mut data = CreatePictureData::default();
+        if self.repeat != Repeat::None {
+            mask |= CreatePictureMask::REPEAT;
+            data.repeat = Some(self.repeat as u32);
+        }
+        if let Some(alpha_map) = self.alpha_map {
+            mask |= CreatePictureMask::ALPHA_MAP;
+            data.alphamap_picture = Some(alpha_map.handle);
+        }
+        if self.alpha_x_origin != 0 {
+            mask |= CreatePictureMask::ALPHA_X_ORIGIN;
+            data.alpha_x_origin = Some(self.alpha_x_origin as i32);
+        }
+        if self.alpha_y_origin != 0 {
+            mask |= CreatePictureMask::ALPHA_Y_ORIGIN;
+            data.alpha_y_origin = Some(self.alpha_y_origin as i32);
+        }
+        if self.clip_x_origin != 0 {
+            mask |= CreatePictureMask::CLIP_X_ORIGIN;
+            data.clip_x_origin = Some(self.clip_x_origin as i32);
+        }
+        if self.clip_y_origin != 0 {
+            mask |= CreatePictureMask::CLIP_Y_ORIGIN;
+            data.clip_y_origin = Some(self.clip_y_origin as i32);
+        }
+        if let Some(clip_mask) = self.clip_mask {
+            mask |= CreatePictureMask::CLIP_MASK;
+            data.clip_mask_pixmap = Some(clip_mask.handle);
+        }
+        if !self.graphics_exposures {
+            mask |= CreatePictureMask::GRAPHICS_EXPOSURE;
+            data.graphics_exposure = Some(0);
+        }
+        if self.subwindow_mode != SubwindowMode::ClipByChildren {
+            mask |= CreatePictureMask::SUBWINDOW_MODE;
+            data.subwindow_mode = Some(render::SubwindowMode::IncludeInferiors);
+        }
+        if self.poly_edge != PolyEdge::Smooth {
+            mask |= CreatePictureMask::POLY_EDGE;
+            data.poly_edge = Some(self.poly_edge);
+        }
+        if self.poly_mode != PolyMode::Precise {
+            mask |= CreatePictureMask::POLY_MODE;
+            data.poly_mode = Some(self.poly_mode);
+        }
+        if let Some(dither) = self.dither {
+            mask |= CreatePictureMask::DITHER;
+            data.dither_atom = Some(dither.handle);
+        }
+        if self.component_alpha {
+            mask |= CreatePictureMask::COMPONENT_ALPHA;
+            data.component_alpha = Some(1);
+        }
+        (mask, data)
+    }
+}
+
+/// A point in 16.16 fixed point
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PointFixed {
+    pub x: I16F16,
+    pub y: I16F16,
+}
+
+impl Into<PointFix> for PointFixed {
+    fn into(self) -> PointFix {
+        PointFix {
+            x: self.x.into(),
+            y: self.y.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Triangle {
+    pub p1: PointFixed,
+    pub p2: PointFixed,
+    pub p3: PointFixed,
+}
+
+impl Into<TriangleFix> for Triangle {
+    fn into(self) -> TriangleFix {
+        TriangleFix {
+            p1: self.p1.into(),
+            p2: self.p2.into(),
+            p3: self.p3.into(),
+        }
+    }
+}
+
+/// A trapezoid between `top` and `bottom`, with left and right edges along the lines through each pair of points
+#[derive(Debug, Clone, Copy)]
+pub struct Trapezoid {
+    pub top: I16F16,
+    pub bottom: I16F16,
+    pub left: (PointFixed, PointFixed),
+    pub right: (PointFixed, PointFixed),
+}
+
+impl Into<TrapezoidFix> for Trapezoid {
+    fn into(self) -> TrapezoidFix {
+        TrapezoidFix {
+            top: self.top.into(),
+            bottom: self.bottom.into(),
+            left: LineFix {
+                p1: self.left.0.into(),
+                p2: self.left.1.into(),
+            },
+            right: LineFix {
+                p1: self.right.0.into(),
+                p2: self.right.1.into(),
+            },
+        }
+    }
+}
+
+/// A horizontal span from `left` to `right` at `y`
+#[derive(Debug, Clone, Copy)]
+pub struct Span {
+    pub left: I16F16,
+    pub right: I16F16,
+    pub y: I16F16,
+}
+
+impl Into<SpanFix> for Span {
+    fn into(self) -> SpanFix {
+        SpanFix {
+            l: self.left.into(),
+            r: self.right.into(),
+            y: self.y.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Trap {
+    pub top: Span,
+    pub bottom: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct Filters {
+    /// for each filter, the index of the filter it is an alias of, or `None`
+    pub aliases: Vec<Option<u16>>,
+    pub filters: Vec<String>,
+}
+
+impl X11Connection {
+    pub async fn create_picture(&self, drawable: impl Into<Drawable<'_>>, format: PictFormat, params: PictureParams<'_>) -> Result<Picture<'_>> {
+        let picture = self.new_resource_id();
+        let (mask, data) = params.into();
+
+        send_request_render!(
+            self,
+            RenderOpcode::CreatePicture,
+            CreatePictureRequest {
+                picture: picture,
+                drawable: drawable.into().handle(),
+                pict_format: format.handle,
+                mask: mask,
+                data: data,
+            }
+        );
+        Ok(Picture {
+            handle: picture,
+            connection: self,
+        })
+    }
+
+    /// Filters supported for pictures on the screen of `drawable`
+    pub async fn query_filters(&self, drawable: impl Into<Drawable<'_>>) -> Result<Filters> {
+        let reply = send_request_render!(
+            self,
+            RenderOpcode::QueryFilters,
+            QueryFiltersResponse,
+            QueryFiltersRequest {
+                drawable: drawable.into().handle(),
+            }
+        )
+        .into_inner();
+
+        Ok(Filters {
+            aliases: reply
+                .aliases
+                .into_iter()
+                .map(|x| match x {
+                    0xFFFF => None,
+                    x => Some(x),
+                })
+                .collect(),
+            filters: reply.filters.into_iter().map(|x| x.string).collect(),
+        })
+    }
+
+    /// Creates an animated cursor cycling through `cursors`, each shown for a delay in milliseconds
+    pub async fn create_anim_cursor(&self, cursors: impl IntoIterator<Item = (Cursor<'_>, u32)>) -> Result<Cursor<'_>> {
+        let cursor = self.new_resource_id();
+
+        send_request_render!(
+            self,
+            RenderOpcode::CreateAnimCursor,
+            CreateAnimCursorRequest {
+                cursor: cursor,
+                cursors: cursors
+                    .into_iter()
+                    .map(|(cursor, delay)| AnimCursorElt {
+                        cursor: cursor.handle,
+                        delay: delay,
+                    })
+                    .collect(),
+            }
+        );
+        Ok(Cursor {
+            handle: cursor,
+            connection: self,
+        })
+    }
+}
+
+impl<'a> Picture<'a> {
+    pub async fn change_attributes(self, params: PictureParams<'_>) -> Result<()> {
+        let (mask, data) = params.into();
+
+        send_request_render!(
+            self.connection,
+            RenderOpcode::ChangePicture,
+            ChangePictureRequest {
+                picture: self.handle,
+                mask: mask,
+                data: data,
+            }
+        );
+        Ok(())
+    }
+
+    pub async fn set_clip_rectangles(self, clip_x_origin: i16, clip_y_origin: i16, rectangles: Vec<Rectangle>) -> Result<()> {
+        send_request_render!(
+            self.connection,
+            RenderOpcode::SetPictureClipRectangles,
+            SetPictureClipRectanglesRequest {
+                picture: self.handle,
+                clip_x_origin: clip_x_origin,
+                clip_y_origin: clip_y_origin,
+                rectangles: rectangles.into_iter().map(Into::into).collect(),
+            }
+        );
+        Ok(())
+    }
+
+    pub async fn free(self) -> Result<()> {
+        send_request_render!(
+            self.connection,
+            RenderOpcode::FreePicture,
+            FreePictureRequest {
+                picture: self.handle,
+            }
+        );
+        Ok(())
+    }
+
+    /// Composites `src` (through `mask`, if set) onto this picture
+    pub async fn composite(
+        self,
+        op: PictOp,
+        src: Picture<'_>,
+        mask: Option<Picture<'_>>,
+        src_x: i16,
+        src_y: i16,
+        mask_x: i16,
+        mask_y: i16,
+        dst_x: i16,
+        dst_y: i16,
+        width: u16,
+        height: u16,
+    ) -> Result<()> {
+        send_request_render!(
+            self.connection,
+            RenderOpcode::Composite,
+            CompositeRequest {
+                op: op,
+                src_picture: src.handle,
+                mask_picture: mask.map(|x| x.handle).unwrap_or(0),
+                dst_picture: self.handle,
+                src_x: src_x,
+                src_y: src_y,
+                mask_x: mask_x,
+                mask_y: mask_y,
+                dst_x: dst_x,
+                dst_y: dst_y,
+                width: width,
+                height: height,
+            }
+        );
+        Ok(())
+    }
+
+    pub async fn fill_rectangles(self, op: PictOp, color: RenderColor, rectangles: Vec<Rectangle>) -> Result<()> {
+        send_request_render!(
+            self.connection,
+            RenderOpcode::FillRectangles,
+            FillRectanglesRequest {
+                op: op,
+                dst_picture: self.handle,
+                color: color,
+                rectangles: rectangles.into_iter().map(Into::into).collect(),
+            }
+        );
+        Ok(())
+    }
+
+    /// Composites `src` onto this picture through the area covered by `trapezoids`.
+    /// Without a `mask_format`, each trapezoid is composited separately.
+    pub async fn trapezoids(
+        self,
+        op: PictOp,
+        src: Picture<'_>,
+        mask_format: Option<PictFormat>,
+        src_x: i16,
+        src_y: i16,
+        trapezoids: Vec<Trapezoid>,
+    ) -> Result<()> {
+        send_request_render!(
+            self.connection,
+            RenderOpcode::Trapezoids,
+            TrapezoidsRequest {
+                op: op,
+                src_picture: src.handle,
+                dst_picture: self.handle,
+                mask_pict_format: mask_format.map(|x| x.handle).unwrap_or(0),
+                src_x: src_x,
+                src_y: src_y,
+                trapezoids: trapezoids.into_iter().map(Into::into).collect(),
+            }
+        );
+        Ok(())
+    }
+
+    pub async fn triangles(
+        self,
+        op: PictOp,
+        src: Picture<'_>,
+        mask_format: Option<PictFormat>,
+        src_x: i16,
+        src_y: i16,
+        triangles: Vec<Triangle>,
+    ) -> Result<()> {
+        send_request_render!(
+            self.connection,
+            RenderOpcode::Triangles,
+            TrianglesRequest {
+                op: op,
+                src_picture: src.handle,
+                dst_picture: self.handle,
+                mask_pict_format: mask_format.map(|x| x.handle).unwrap_or(0),
+                src_x: src_x,
+                src_y: src_y,
+                triangles: triangles.into_iter().map(Into::into).collect(),
+            }
+        );
+        Ok(())
+    }
+
+    pub async fn tri_strip(self, op: PictOp, src: Picture<'_>, mask_format: Option<PictFormat>, src_x: i16, src_y: i16, points: Vec<PointFixed>) -> Result<()> {
+        send_request_render!(
+            self.connection,
+            RenderOpcode::TriStrip,
+            TriStripRequest {
+                op: op,
+                src_picture: src.handle,
+                dst_picture: self.handle,
+                mask_pict_format: mask_format.map(|x| x.handle).unwrap_or(0),
+                src_x: src_x,
+                src_y: src_y,
+                points: points.into_iter().map(Into::into).collect(),
+            }
+        );
+        Ok(())
+    }
+
+    pub async fn tri_fan(self, op: PictOp, src: Picture<'_>, mask_format: Option<PictFormat>, src_x: i16, src_y: i16, points: Vec<PointFixed>) -> Result<()> {
+        send_request_render!(
+            self.connection,
+            RenderOpcode::TriFan,
+            TriFanRequest {
+                op: op,
+                src_picture: src.handle,
+                dst_picture: self.handle,
+                mask_pict_format: mask_format.map(|x| x.handle).unwrap_or(0),
+                src_x: src_x,
+                src_y: src_y,
+                points: points.into_iter().map(Into::into).collect(),
+            }
+        );
+        Ok(())
+    }
+
+    /// Adds `traps` to this picture's alpha channel, which must be an alpha-only format
+    pub async fn add_traps(self, x_off: i16, y_off: i16, traps: Vec<Trap>) -> Result<()> {
+        send_request_render!(
+            self.connection,
+            RenderOpcode::AddTraps,
+            AddTrapsRequest {
+                picture: self.handle,
+                x_off: x_off,
+                y_off: y_off,
+                traps: traps
+                    .into_iter()
+                    .map(|trap| render::Trap {
+                        top: trap.top.into(),
+                        bottom: trap.bottom.into(),
+                    })
+                    .collect(),
+            }
+        );
+        Ok(())
+    }
+
+    /// Sets the transform from destination to source space, applied when this picture is used as a source or mask
+    pub async fn set_transform(self, transform: [[I16F16; 3]; 3]) -> Result<()> {
+        send_request_render!(
+            self.connection,
+            RenderOpcode::SetPictureTransform,
+            SetPictureTransformRequest {
+                picture: self.handle,
+                transform: Transform(
+                    transform
+                        .into_iter()
+                        .flat_map(|x| x.into_iter().map(|x| -> render::Fp1616 { x.into() }))
+                        .collect()
+                ),
+            }
+        );
+        Ok(())
+    }
+
+    /// Sets the filter used when sampling this picture, i.e. `nearest`, `bilinear` or `convolution`
+    pub async fn set_filter(self, filter: impl AsRef<str>, values: impl IntoIterator<Item = I16F16>) -> Result<()> {
+        send_request_render!(
+            self.connection,
+            RenderOpcode::SetPictureFilter,
+            SetPictureFilterRequest {
+                picture: self.handle,
+                filter: filter.as_ref().to_string(),
+                values: values.into_iter().map(Into::into).collect(),
+            }
+        );
+        Ok(())
+    }
+
+    /// Creates a cursor from this picture, with the hotspot at `x`, `y`
+    pub async fn create_cursor(self, x: u16, y: u16) -> Result<Cursor<'a>> {
+        let cursor = self.connection.new_resource_id();
+
+        send_request_render!(
+            self.connection,
+            RenderOpcode::CreateCursor,
+            CreateCursorRequest {
+                cursor: cursor,
+                src_picture: self.handle,
+                x: x,
+                y: y,
+            }
+        );
+        Ok(Cursor {
+            handle: cursor,
+            connection: self.connection,
+        })
+    }
+}
+
+impl<'a> Resource<'a> for Picture<'a> {
+    fn x11_handle(&self) -> u32 {
+        self.handle
+    }
+
+    fn from_x11_handle(connection: &'a X11Connection, handle: u32) -> Self {
+        Self {
+            connection,
+            handle,
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use crate::coding::screensaver::{QueryInfoRequest, QueryInfoResponse, SelectInputRequest, SetAttributesRequest, SuspendRequest, UnsetAttributesRequest};
+pub use crate::coding::screensaver::{ScreenSaverInputMask, ScreenSaverKind, ScreenSaverState};
+
+use super::*;
+
+#[derive(Debug, Clone)]
+pub struct ScreenSaverInfo<'a> {
+    pub state: ScreenSaverState,
+    /// the window the screen saver is drawn in, while it is active
+    pub saver_window: Option<Window<'a>>,
+    /// milliseconds until the screen saver activates while it is off, or since it activated while it is on
+    pub til_or_since: u32,
+    /// milliseconds since the last user input
+    pub idle: u32,
+    /// events selected by this client
+    pub event_mask: ScreenSaverInputMask,
+    pub kind: ScreenSaverKind,
+}
+
+impl X11Connection {
+    /// Prevents the screen saver from activating while `suspend` is set, such as during video playback.
+    /// Suspensions nest, and are released when the connection closes.
+    pub async fn screensaver_suspend(&self, suspend: bool) -> Result<()> {
+        send_request_screensaver!(
+            self,
+            ScreenSaverOpcode::Suspend,
+            SuspendRequest {
+                suspend: suspend as u32,
+            }
+        );
+        Ok(())
+    }
+}
+
+impl<'a> Window<'a> {
+    /// Screen saver state of the screen this window is on
+    pub async fn screensaver_info(self) -> Result<ScreenSaverInfo<'a>> {
+        let reply = send_request_screensaver!(
+            self.connection,
+            ScreenSaverOpcode::QueryInfo,
+            QueryInfoResponse,
+            QueryInfoRequest {
+                drawable: self.handle,
+            }
+        );
+        let state = ScreenSaverState::from_repr(reply.reserved)?;
+        let reply = reply.into_inner();
+
+        Ok(ScreenSaverInfo {
+            state,
+            saver_window: match reply.saver_window {
+                0 => None,
+                handle => Some(Window {
+                    handle,
+                    connection: self.connection,
+                }),
+            },
+            til_or_since: reply.ms_until_server,
+            idle: reply.ms_since_user_input,
+            event_mask: reply.event_mask,
+            kind: reply.kind,
+        })
+    }
+
+    /// Selects screen saver events for the screen this window is on, see [`crate::events::ScreenSaverNotifyEvent`]
+    pub async fn screensaver_select_input(self, event_mask: ScreenSaverInputMask) -> Result<()> {
+        send_request_screensaver!(
+            self.connection,
+            ScreenSaverOpcode::SelectInput,
+            SelectInputRequest {
+                drawable: self.handle,
+                event_mask: event_mask,
+            }
+        );
+        Ok(())
+    }
+
+    /// Sets the attributes of the window the server creates for the screen saver on this window's screen.
+    /// The `parent` of `params` is ignored, the screen saver window is always a child of the root window.
+    pub async fn screensaver_set_attributes(self, params: WindowParams<'_>) -> Result<()> {
+        let attributes: crate::coding::WindowAttributes = params.attributes.into();
+        let mut encoded = vec![];
+        attributes.encode_sync(&mut encoded)?;
+
+        send_request_screensaver!(
+            self.connection,
+            ScreenSaverOpcode::SetAttributes,
+            SetAttributesRequest {
+                drawable: self.handle,
+                x: params.x,
+                y: params.y,
+                width: params.width,
+                height: params.height,
+                border_width: params.border_width,
+                class: params.window_class as u8,
+                depth: params.depth,
+                visual_id: match params.visual {
+                    WindowVisual::CopyFromParent => 0,
+                    WindowVisual::Some(x) => x.handle,
+                },
+                attributes: encoded,
+            }
+        );
+        Ok(())
+    }
+
+    /// Discards the attributes set by [`Window::screensaver_set_attributes`], so the server's default screen saver window is used
+    pub async fn screensaver_unset_attributes(self) -> Result<()> {
+        send_request_screensaver!(
+            self.connection,
+            ScreenSaverOpcode::UnsetAttributes,
+            UnsetAttributesRequest {
+                drawable: self.handle,
+            }
+        );
+        Ok(())
+    }
+}
@@ -18,6 +18,7 @@ impl X11Connection {
                 event_start: queried.first_event,
                 error_start: queried.first_error,
                 event_count: SHAPE_EVENT_COUNT,
+                version: None,
             },
         );
 
@@ -26,6 +27,7 @@ impl X11Connection {
         if reply.major_version != 1 {
             bail!("unsupported shape version on server: {}.{}", reply.major_version, reply.minor_version);
         }
+        self.set_ext_version(SHAPE_EXT_NAME, reply.major_version, reply.minor_version);
         Ok(())
     }
 }
@@ -0,0 +1,170 @@
+use super::*;
+
+/// An image buffer that lives in shared memory when MIT-SHM is usable, and in local memory sent over the socket otherwise,
+/// such as on TCP connections.
+#[derive(derivative::Derivative)]
+#[derivative(Debug)]
+pub struct ShmImage<'a> {
+    #[derivative(Debug = "ignore")]
+    connection: &'a X11Connection,
+    pub format: ImageFormat,
+    pub width: u16,
+    pub height: u16,
+    pub depth: u8,
+    stride: usize,
+    storage: ImageStorage<'a>,
+}
+
+#[derive(Debug)]
+enum ImageStorage<'a> {
+    Shared(ShmSegment<'a>),
+    Local(Vec<u8>),
+}
+
+impl X11Connection {
+    /// Creates a zeroed image of the given layout, shared with the server if possible
+    pub async fn create_shm_image(&self, format: ImageFormat, width: u16, height: u16, depth: u8) -> Result<ShmImage<'_>> {
+        if format == ImageFormat::Bitmap {
+            bail!("shm images must be XYPixmap or ZPixmap");
+        }
+        let (stride, planes) = match self.image_layout(format, width, 0, depth) {
+            Some(layout) => layout,
+            None => bail!("no pixmap format for depth {}", depth),
+        };
+        let size = stride * planes * height as usize;
+
+        let storage = if self.shm_available() {
+            match self.create_shm_segment(size.max(1), false).await {
+                Ok(segment) => ImageStorage::Shared(segment),
+                Err(e) => {
+                    debug!("failed to create shm segment, falling back to local image: {:?}", e);
+                    ImageStorage::Local(vec![0; size])
+                }
+            }
+        } else {
+            ImageStorage::Local(vec![0; size])
+        };
+
+        Ok(ShmImage {
+            connection: self,
+            format,
+            width,
+            height,
+            depth,
+            stride,
+            storage,
+        })
+    }
+}
+
+impl<'a> ShmImage<'a> {
+    /// Bytes per scanline
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    pub fn is_shared(&self) -> bool {
+        matches!(self.storage, ImageStorage::Shared(_))
+    }
+
+    pub fn segment(&self) -> Option<&ShmSegment<'a>> {
+        match &self.storage {
+            ImageStorage::Shared(segment) => Some(segment),
+            ImageStorage::Local(_) => None,
+        }
+    }
+
+    fn size(&self) -> usize {
+        let planes = match self.format {
+            ImageFormat::XYPixmap => self.depth as usize,
+            _ => 1,
+        };
+        self.stride * planes * self.height as usize
+    }
+
+    /// The image data, see [`ShmSegment::data`]
+    pub fn data(&mut self) -> &[u8] {
+        let size = self.size();
+        match &mut self.storage {
+            ImageStorage::Shared(segment) => &segment.data()[..size],
+            ImageStorage::Local(data) => &data[..],
+        }
+    }
+
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        let size = self.size();
+        match &mut self.storage {
+            ImageStorage::Shared(segment) => &mut segment.data_mut()[..size],
+            ImageStorage::Local(data) => &mut data[..],
+        }
+    }
+
+    /// Creates a pixmap with the contents of this image. The pixmap shares the image memory if the server supports
+    /// shared pixmaps in this format, otherwise the contents are copied.
+    pub async fn create_pixmap(&self, drawable: impl Into<Drawable<'_>>) -> Result<Pixmap<'a>> {
+        let drawable = drawable.into();
+        if let ImageStorage::Shared(segment) = &self.storage {
+            let shareable = self
+                .connection
+                .shm_version()
+                .map_or(false, |version| version.shared_pixmaps && version.pixmap_format == self.format);
+            if shareable {
+                return self
+                    .connection
+                    .create_shm_pixmap(drawable, segment, 0, self.width, self.height, self.depth)
+                    .await;
+            }
+        }
+
+        let pixmap = self.connection.create_pixmap_with_depth(self.depth, drawable, self.width, self.height).await?;
+        let gcontext = self.connection.create_gcontext(pixmap, Default::default()).await?;
+        let result = self.put(gcontext, pixmap, 0, 0).await;
+        gcontext.free().await?;
+        result?;
+        Ok(pixmap)
+    }
+
+    /// Draws the whole image at `dst_x`, `dst_y`.
+    /// Shared images wait for the server to finish reading the segment, so the image can be modified once this returns.
+    pub async fn put(&self, gcontext: GContext<'_>, drawable: impl Into<Drawable<'_>>, dst_x: i16, dst_y: i16) -> Result<()> {
+        match &self.storage {
+            ImageStorage::Shared(segment) => {
+                gcontext
+                    .shm_put_image(drawable, segment, 0, self.format, self.depth, self.width, self.height, 0, 0, self.width, self.height, dst_x, dst_y)
+                    .await?;
+                self.connection.sync().await
+            }
+            ImageStorage::Local(data) => {
+                gcontext
+                    .put_image(drawable, self.format, self.width, self.height, dst_x, dst_y, 0, self.depth, data.clone())
+                    .await
+            }
+        }
+    }
+
+    /// Fills the image with the area of `drawable` at `x`, `y` with the image's size, returning the visual of the drawable
+    pub async fn get(&mut self, gcontext: GContext<'_>, drawable: impl Into<Drawable<'_>>, x: i16, y: i16, plane_mask: u32) -> Result<Option<Visual>> {
+        match &mut self.storage {
+            ImageStorage::Shared(segment) => {
+                let info = gcontext
+                    .shm_get_image(drawable, segment, 0, self.format, x, y, self.width, self.height, plane_mask)
+                    .await?;
+                Ok(info.visual)
+            }
+            ImageStorage::Local(data) => {
+                let fetched = gcontext.get_image(drawable, self.format, x, y, self.width, self.height, plane_mask).await?;
+                let len = fetched.data.len().min(data.len());
+                data[..len].copy_from_slice(&fetched.data[..len]);
+                Ok(fetched.visual)
+            }
+        }
+    }
+
+    /// Detaches the shared memory from the server, if any
+    pub async fn free(self) -> Result<()> {
+        match self.storage {
+            ImageStorage::Shared(segment) => segment.detach().await,
+            ImageStorage::Local(_) => Ok(()),
+        }
+    }
+}
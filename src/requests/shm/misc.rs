@@ -0,0 +1,70 @@
+use crate::{
+    coding::shm::{QueryVersionRequest, QueryVersionResponse},
+    net::{ExtInfo, Extension},
+};
+
+use super::*;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ShmVersion {
+    pub major_version: u16,
+    pub minor_version: u16,
+    pub uid: u16,
+    pub gid: u16,
+    /// format of shared memory pixmaps, only meaningful if `shared_pixmaps` is set
+    pub pixmap_format: ImageFormat,
+    pub shared_pixmaps: bool,
+}
+
+impl X11Connection {
+    pub(crate) async fn enable_shm(&self) -> Result<()> {
+        // query_extension
+        let queried = self.query_extension(SHM_EXT_NAME).await?;
+        ensure!(queried.present, "mit-shm missing on x11 server");
+        self.0.registered_extensions.insert(
+            SHM_EXT_NAME.to_string(),
+            ExtInfo {
+                extension: Extension::Shm,
+                major_opcode: queried.major_opcode,
+                event_start: queried.first_event,
+                error_start: queried.first_error,
+                event_count: SHM_EVENT_COUNT,
+                version: None,
+            },
+        );
+
+        // enable extension
+        let version = self.shm_query_version().await?;
+        if version.major_version != 1 {
+            bail!("unsupported mit-shm version on server: {}.{}", version.major_version, version.minor_version);
+        }
+        self.set_ext_version(SHM_EXT_NAME, version.major_version, version.minor_version);
+        self.0.shm_version.set(version).ok();
+        Ok(())
+    }
+
+    /// Whether shared memory segments can be used, which requires the server to run on this machine
+    pub fn shm_available(&self) -> bool {
+        self.is_local() && self.get_ext_info(SHM_EXT_NAME).is_some()
+    }
+
+    /// The mit-shm version negotiated when the extension was enabled
+    pub fn shm_version(&self) -> Option<ShmVersion> {
+        self.0.shm_version.get().copied()
+    }
+
+    pub async fn shm_query_version(&self) -> Result<ShmVersion> {
+        let reply = send_request_shm!(self, ShmOpcode::QueryVersion, QueryVersionResponse, QueryVersionRequest {});
+        let shared_pixmaps = reply.reserved != 0;
+        let reply = reply.into_inner();
+
+        Ok(ShmVersion {
+            major_version: reply.major_version,
+            minor_version: reply.minor_version,
+            uid: reply.uid,
+            gid: reply.gid,
+            pixmap_format: ImageFormat::from_repr(reply.pixmap_format)?,
+            shared_pixmaps,
+        })
+    }
+}
@@ -0,0 +1,58 @@
+use super::*;
+use crate::coding::shm::ShmOpcode;
+use crate::coding::RequestBody;
+
+pub const SHM_EXT_NAME: &str = "MIT-SHM";
+const SHM_EVENT_COUNT: u8 = 1;
+
+macro_rules! send_request_shm {
+    ($self_:expr, $opcode:expr, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let ext_code = $self_.0.registered_extensions.get(SHM_EXT_NAME).unwrap().major_opcode;
+            send_request_ext!($self_, ext_code, $opcode, $name { $($key: $value,)* })
+        }
+    };
+    ($self_:expr, $opcode:expr, $reply:ident, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let ext_code = $self_.0.registered_extensions.get(SHM_EXT_NAME).unwrap().major_opcode;
+            send_request_ext!($self_, ext_code, $opcode, $reply, $name { $($key: $value,)* })
+        }
+    };
+    ($self_:expr, $opcode:expr, parse_reserved $reply:ident, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let ext_code = $self_.0.registered_extensions.get(SHM_EXT_NAME).unwrap().major_opcode;
+            send_request_ext!($self_, ext_code, $opcode, parse_reserved $reply, $name { $($key: $value,)* })
+        }
+    };
+    ($self_:expr, $opcode:expr, fds $fds:expr, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let ext_code = $self_.0.registered_extensions.get(SHM_EXT_NAME).unwrap().major_opcode;
+            send_request_ext!($self_, ext_code, $opcode, fds $fds, $name { $($key: $value,)* })
+        }
+    };
+    ($self_:expr, $opcode:expr, receive_fds $reply:ident, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let ext_code = $self_.0.registered_extensions.get(SHM_EXT_NAME).unwrap().major_opcode;
+            send_request_ext!($self_, ext_code, $opcode, receive_fds $reply, $name { $($key: $value,)* })
+        }
+    };
+    ($self_:expr, $opcode:expr, stream, $reply:ident, $name:ident { $($key:ident: $value:expr,)* }) => {
+        {
+            let ext_code = $self_.0.registered_extensions.get(SHM_EXT_NAME).unwrap().major_opcode;
+            send_request_ext!($self_, ext_code, $opcode, stream, $reply, $name { $($key: $value,)* })
+        }
+    };
+}
+
+mod misc;
+pub use misc::*;
+
+#[cfg(unix)]
+mod segment;
+#[cfg(unix)]
+pub use segment::*;
+
+#[cfg(unix)]
+mod image;
+#[cfg(unix)]
+pub use image::*;
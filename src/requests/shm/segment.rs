@@ -0,0 +1,347 @@
+use std::{os::unix::io::AsRawFd, ptr::NonNull};
+
+use crate::{
+    coding::shm::{
+        AttachFdRequest, AttachRequest, CreatePixmapRequest, CreateSegmentRequest, CreateSegmentResponse, DetachRequest, GetImageRequest, GetImageResponse,
+        PutImageRequest,
+    },
+    connection::OwnedFd,
+};
+
+use super::*;
+
+/// Memory shared with the x11 server. The server keeps its attachment until [`ShmSegment::detach`] is called or the connection closes,
+/// our own mapping is released when this is dropped.
+#[derive(derivative::Derivative)]
+#[derivative(Debug)]
+pub struct ShmSegment<'a> {
+    pub(crate) handle: u32,
+    #[derivative(Debug = "ignore")]
+    pub(crate) connection: &'a X11Connection,
+    #[derivative(Debug = "ignore")]
+    memory: ShmMemory,
+    read_only: bool,
+}
+
+struct ShmMemory {
+    address: NonNull<u8>,
+    size: usize,
+    // attached with shmat rather than mapped from a file descriptor
+    sysv: bool,
+}
+
+// the mapping is only accessed through `ShmSegment`, which follows borrowing rules
+unsafe impl Send for ShmMemory {}
+unsafe impl Sync for ShmMemory {}
+
+impl ShmMemory {
+    /// Creates and maps a private SysV segment, returning its id
+    fn create_sysv(size: usize) -> Result<(i32, Self)> {
+        let shmid = unsafe { libc::shmget(libc::IPC_PRIVATE, size, libc::IPC_CREAT | 0o600) };
+        if shmid < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        let address = unsafe { libc::shmat(shmid, std::ptr::null(), 0) };
+        if address as isize == -1 {
+            let error = std::io::Error::last_os_error();
+            unsafe { libc::shmctl(shmid, libc::IPC_RMID, std::ptr::null_mut()) };
+            return Err(error.into());
+        }
+        Ok((
+            shmid,
+            Self {
+                address: NonNull::new(address as *mut u8).unwrap(),
+                size,
+                sysv: true,
+            },
+        ))
+    }
+
+    /// Maps `size` bytes of a shared memory file descriptor. `read_only` only restricts the server's mapping, we always map it writable.
+    fn map_fd(fd: &OwnedFd, size: usize) -> Result<Self> {
+        let address = unsafe { libc::mmap(std::ptr::null_mut(), size, libc::PROT_READ | libc::PROT_WRITE, libc::MAP_SHARED, fd.as_raw_fd(), 0) };
+        if address == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        Ok(Self {
+            address: NonNull::new(address as *mut u8).unwrap(),
+            size,
+            sysv: false,
+        })
+    }
+}
+
+impl Drop for ShmMemory {
+    fn drop(&mut self) {
+        match self.sysv {
+            true => unsafe { libc::shmdt(self.address.as_ptr() as *const libc::c_void) },
+            false => unsafe { libc::munmap(self.address.as_ptr() as *mut libc::c_void, self.size) },
+        };
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ShmImageInfo {
+    pub depth: u8,
+    pub visual: Option<Visual>,
+    /// bytes written into the segment
+    pub size: u32,
+}
+
+impl X11Connection {
+    /// Creates a SysV shared memory segment of `size` bytes and attaches it to the server
+    pub async fn create_shm_segment(&self, size: usize, read_only: bool) -> Result<ShmSegment<'_>> {
+        if !self.shm_available() {
+            bail!("mit-shm is not available on this connection");
+        }
+        let (shmid, memory) = ShmMemory::create_sysv(size)?;
+        let segment = ShmSegment {
+            handle: self.new_resource_id(),
+            connection: self,
+            memory,
+            read_only,
+        };
+
+        let attached = self.checked(segment.attach(shmid, read_only)).await;
+        // both sides are attached (or the server failed to), so the segment can be destroyed once they detach
+        unsafe { libc::shmctl(shmid, libc::IPC_RMID, std::ptr::null_mut()) };
+        attached?;
+
+        Ok(segment)
+    }
+
+    /// Creates an anonymous shared memory file of `size` bytes and passes it to the server, which requires mit-shm 1.2
+    #[cfg(target_os = "linux")]
+    pub async fn create_shm_segment_fd(&self, size: usize, read_only: bool) -> Result<ShmSegment<'_>> {
+        use std::os::unix::io::FromRawFd;
+
+        if !self.shm_available() {
+            bail!("mit-shm is not available on this connection");
+        }
+
+        let raw = unsafe { libc::memfd_create(b"rx11-shm\0".as_ptr() as *const libc::c_char, libc::MFD_CLOEXEC) };
+        if raw < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        let fd = unsafe { OwnedFd::from_raw_fd(raw) };
+        if unsafe { libc::ftruncate(fd.as_raw_fd(), size as libc::off_t) } < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        let memory = ShmMemory::map_fd(&fd, size)?;
+        let segment = ShmSegment {
+            handle: self.new_resource_id(),
+            connection: self,
+            memory,
+            read_only,
+        };
+
+        send_request_shm!(
+            self,
+            ShmOpcode::AttachFd,
+            fds vec![fd],
+            AttachFdRequest {
+                shmseg: segment.handle,
+                read_only: read_only,
+            }
+        );
+
+        Ok(segment)
+    }
+
+    /// Has the server allocate a segment of `size` bytes and send it to us, which requires mit-shm 1.2
+    pub async fn create_server_shm_segment(&self, size: usize, read_only: bool) -> Result<ShmSegment<'_>> {
+        if !self.shm_available() {
+            bail!("mit-shm is not available on this connection");
+        }
+        let server_size = u32::try_from(size)?;
+        let handle = self.new_resource_id();
+        let (_, fds) = send_request_shm!(
+            self,
+            ShmOpcode::CreateSegment,
+            receive_fds CreateSegmentResponse,
+            CreateSegmentRequest {
+                shmseg: handle,
+                size: server_size,
+                read_only: read_only,
+            }
+        );
+        let fd = match fds.into_iter().next() {
+            Some(fd) => fd,
+            None => bail!("x11 server did not send a file descriptor for the shm segment"),
+        };
+
+        Ok(ShmSegment {
+            handle,
+            connection: self,
+            memory: ShmMemory::map_fd(&fd, size)?,
+            read_only,
+        })
+    }
+
+    /// Creates a pixmap whose contents live in `segment` at `offset`, see [`ShmVersion::shared_pixmaps`]
+    pub async fn create_shm_pixmap(
+        &self,
+        drawable: impl Into<Drawable<'_>>,
+        segment: &ShmSegment<'_>,
+        offset: u32,
+        width: u16,
+        height: u16,
+        depth: u8,
+    ) -> Result<Pixmap<'_>> {
+        let pixmap = self.new_resource_id();
+
+        send_request_shm!(
+            self,
+            ShmOpcode::CreatePixmap,
+            CreatePixmapRequest {
+                pixmap: pixmap,
+                drawable: drawable.into().handle(),
+                width: width,
+                height: height,
+                depth: depth,
+                shmseg: segment.handle,
+                offset: offset,
+            }
+        );
+
+        Ok(Pixmap {
+            handle: pixmap,
+            connection: self,
+        })
+    }
+}
+
+impl<'a> ShmSegment<'a> {
+    async fn attach(&self, shmid: i32, read_only: bool) -> Result<()> {
+        send_request_shm!(
+            self.connection,
+            ShmOpcode::Attach,
+            AttachRequest {
+                shmseg: self.handle,
+                shmid: shmid as u32,
+                read_only: read_only,
+            }
+        );
+        Ok(())
+    }
+
+    pub fn size(&self) -> usize {
+        self.memory.size
+    }
+
+    /// Whether the server attached the segment read-only, in which case images can't be fetched into it
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// The shared memory. Borrows the segment mutably, as the server only writes it during [`GContext::shm_get_image`],
+    /// which does too. The segment must not be written through other means, such as requests using its raw id, while this is borrowed.
+    pub fn data(&mut self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.memory.address.as_ptr(), self.memory.size) }
+    }
+
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.memory.address.as_ptr(), self.memory.size) }
+    }
+
+    pub async fn detach(self) -> Result<()> {
+        send_request_shm!(
+            self.connection,
+            ShmOpcode::Detach,
+            DetachRequest {
+                shmseg: self.handle,
+            }
+        );
+        Ok(())
+    }
+}
+
+impl<'a> GContext<'a> {
+    /// Draws the `src_width`x`src_height` area at `src_x`, `src_y` of the `total_width`x`total_height` image stored at `offset` in `segment`
+    pub async fn shm_put_image(
+        self,
+        drawable: impl Into<Drawable<'_>>,
+        segment: &ShmSegment<'_>,
+        offset: u32,
+        format: ImageFormat,
+        depth: u8,
+        total_width: u16,
+        total_height: u16,
+        src_x: u16,
+        src_y: u16,
+        src_width: u16,
+        src_height: u16,
+        dst_x: i16,
+        dst_y: i16,
+    ) -> Result<()> {
+        send_request_shm!(
+            self.connection,
+            ShmOpcode::PutImage,
+            PutImageRequest {
+                drawable: drawable.into().handle(),
+                gcontext: self.handle,
+                total_width: total_width,
+                total_height: total_height,
+                src_x: src_x,
+                src_y: src_y,
+                src_width: src_width,
+                src_height: src_height,
+                dst_x: dst_x,
+                dst_y: dst_y,
+                depth: depth,
+                format: format as u8,
+                send_event: false,
+                shmseg: segment.handle,
+                offset: offset,
+            }
+        );
+        Ok(())
+    }
+
+    /// Like [`GContext::get_image`], but the image is written into `segment` at `offset`
+    pub async fn shm_get_image(
+        self,
+        drawable: impl Into<Drawable<'_>>,
+        segment: &mut ShmSegment<'_>,
+        offset: u32,
+        format: ImageFormat,
+        x: i16,
+        y: i16,
+        width: u16,
+        height: u16,
+        plane_mask: u32,
+    ) -> Result<ShmImageInfo> {
+        if format == ImageFormat::Bitmap {
+            bail!("cannot request bitmap image from x11");
+        }
+        let reply = send_request_shm!(
+            self.connection,
+            ShmOpcode::GetImage,
+            GetImageResponse,
+            GetImageRequest {
+                drawable: drawable.into().handle(),
+                x: x,
+                y: y,
+                width: width,
+                height: height,
+                plane_mask: plane_mask,
+                format: format as u8,
+                shmseg: segment.handle,
+                offset: offset,
+            }
+        );
+        let depth = reply.reserved;
+        let reply = reply.into_inner();
+
+        Ok(ShmImageInfo {
+            depth,
+            visual: match reply.visual_id {
+                0 => None,
+                handle => Some(Visual {
+                    handle,
+                }),
+            },
+            size: reply.size,
+        })
+    }
+}
@@ -0,0 +1,155 @@
+use derive_builder::Builder;
+
+use crate::coding::sync::{AlarmData, ChangeAlarmRequest, CreateAlarmRequest, DestroyAlarmRequest, QueryAlarmRequest, QueryAlarmResponse};
+pub use crate::coding::sync::{AlarmMask, AlarmState};
+
+use super::*;
+
+/// Sends AlarmNotify events when its trigger is met
+#[derive(Clone, Copy, derivative::Derivative)]
+#[derivative(Debug)]
+pub struct Alarm<'a> {
+    pub(crate) handle: u32,
+    #[derivative(Debug = "ignore")]
+    pub(crate) connection: &'a X11Connection,
+}
+
+/// Alarm attributes, unset attributes keep their current value or the server's default
+#[derive(Default, Builder, Debug)]
+#[builder(default)]
+pub struct AlarmParams<'a> {
+    /// `Some(None)` clears the counter, making the alarm inactive
+    #[builder(setter(into, strip_option), default)]
+    pub counter: Option<Option<Counter<'a>>>,
+    #[builder(setter(into, strip_option), default)]
+    pub value_type: Option<ValueType>,
+    #[builder(setter(into, strip_option), default)]
+    pub value: Option<i64>,
+    #[builder(setter(into, strip_option), default)]
+    pub test_type: Option<TestType>,
+    /// added to the value each time the alarm triggers
+    #[builder(setter(into, strip_option), default)]
+    pub delta: Option<i64>,
+    /// whether this client receives AlarmNotify events
+    #[builder(setter(into, strip_option), default)]
+    pub events: Option<bool>,
+}
+
+impl<'a> Into<(AlarmMask, AlarmData)> for AlarmParams<'a> {
+    fn into(self) -> (AlarmMask, AlarmData) {
+        let mut mask = AlarmMask::ZERO;
+        let mut data = AlarmData::default();
+        if let Some(counter) = self.counter {
+            mask |= AlarmMask::COUNTER;
+            data.counter = Some(counter.map(|x| x.handle).unwrap_or(0));
+        }
+        if let Some(value_type) = self.value_type {
+            mask |= AlarmMask::VALUE_TYPE;
+            data.value_type = Some(value_type);
+        }
+        if let Some(value) = self.value {
+            mask |= AlarmMask::VALUE;
+            data.value = Some(value);
+        }
+        if let Some(test_type) = self.test_type {
+            mask |= AlarmMask::TEST_TYPE;
+            data.test_type = Some(test_type);
+        }
+        if let Some(delta) = self.delta {
+            mask |= AlarmMask::DELTA;
+            data.delta = Some(delta);
+        }
+        if let Some(events) = self.events {
+            mask |= AlarmMask::EVENTS;
+            data.events = Some(events as u32);
+        }
+        (mask, data)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct AlarmInfo<'a> {
+    pub trigger: SyncTrigger<'a>,
+    pub delta: i64,
+    pub events: bool,
+    pub state: AlarmState,
+}
+
+impl X11Connection {
+    pub async fn create_alarm(&self, params: AlarmParams<'_>) -> Result<Alarm<'_>> {
+        let alarm = self.new_resource_id();
+        let (mask, data) = params.into();
+        send_request_sync!(
+            self,
+            SyncOpcode::CreateAlarm,
+            CreateAlarmRequest {
+                alarm: alarm,
+                mask: mask,
+                data: data,
+            }
+        );
+
+        Ok(Alarm {
+            handle: alarm,
+            connection: self,
+        })
+    }
+}
+
+impl<'a> Alarm<'a> {
+    pub async fn change(self, params: AlarmParams<'_>) -> Result<()> {
+        let (mask, data) = params.into();
+        send_request_sync!(
+            self.connection,
+            SyncOpcode::ChangeAlarm,
+            ChangeAlarmRequest {
+                alarm: self.handle,
+                mask: mask,
+                data: data,
+            }
+        );
+        Ok(())
+    }
+
+    pub async fn query(self) -> Result<AlarmInfo<'a>> {
+        let reply = send_request_sync!(
+            self.connection,
+            SyncOpcode::QueryAlarm,
+            QueryAlarmResponse,
+            QueryAlarmRequest {
+                alarm: self.handle,
+            }
+        );
+
+        Ok(AlarmInfo {
+            trigger: SyncTrigger::from_protocol(self.connection, reply.trigger),
+            delta: reply.delta,
+            events: reply.events,
+            state: reply.state,
+        })
+    }
+
+    pub async fn destroy(self) -> Result<()> {
+        send_request_sync!(
+            self.connection,
+            SyncOpcode::DestroyAlarm,
+            DestroyAlarmRequest {
+                alarm: self.handle,
+            }
+        );
+        Ok(())
+    }
+}
+
+impl<'a> Resource<'a> for Alarm<'a> {
+    fn x11_handle(&self) -> u32 {
+        self.handle
+    }
+
+    fn from_x11_handle(connection: &'a X11Connection, handle: u32) -> Self {
+        Self {
+            connection,
+            handle,
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::coding::sync::{
+    self, AwaitRequest, ChangeCounterRequest, CreateCounterRequest, DestroyCounterRequest, QueryCounterRequest, QueryCounterResponse, SetCounterRequest,
+};
+pub use crate::coding::sync::{TestType, ValueType};
+
+use super::*;
+
+/// A 64 bit value that clients can wait on
+#[derive(Clone, Copy, derivative::Derivative)]
+#[derivative(Debug)]
+pub struct Counter<'a> {
+    pub(crate) handle: u32,
+    #[derivative(Debug = "ignore")]
+    pub(crate) connection: &'a X11Connection,
+}
+
+/// Condition met when `counter` compares to `wait_value` as described by `test_type`
+#[derive(Debug, Clone, Copy)]
+pub struct SyncTrigger<'a> {
+    /// the trigger is always met if `None`
+    pub counter: Option<Counter<'a>>,
+    pub value_type: ValueType,
+    /// relative to the counter's value at the time of the request if `value_type` is [`ValueType::Relative`]
+    pub wait_value: i64,
+    pub test_type: TestType,
+}
+
+impl<'a> SyncTrigger<'a> {
+    pub(crate) fn from_protocol(connection: &'a X11Connection, trigger: sync::Trigger) -> Self {
+        Self {
+            counter: match trigger.counter {
+                0 => None,
+                handle => Some(Counter {
+                    handle,
+                    connection,
+                }),
+            },
+            value_type: trigger.value_type,
+            wait_value: trigger.wait_value,
+            test_type: trigger.test_type,
+        }
+    }
+
+    pub(crate) fn to_protocol(self) -> sync::Trigger {
+        sync::Trigger {
+            counter: self.counter.map(|x| x.handle).unwrap_or(0),
+            value_type: self.value_type,
+            wait_value: self.wait_value,
+            test_type: self.test_type,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct WaitCondition<'a> {
+    pub trigger: SyncTrigger<'a>,
+    /// a CounterNotify is sent if the counter is this far past the wait value when the await finishes
+    pub event_threshold: i64,
+}
+
+impl X11Connection {
+    pub async fn create_counter(&self, initial_value: i64) -> Result<Counter<'_>> {
+        let counter = self.new_resource_id();
+        send_request_sync!(
+            self,
+            SyncOpcode::CreateCounter,
+            CreateCounterRequest {
+                counter: counter,
+                initial_value: initial_value,
+            }
+        );
+
+        Ok(Counter {
+            handle: counter,
+            connection: self,
+        })
+    }
+
+    /// Blocks processing of this client's requests until any of `conditions` is met
+    pub async fn sync_await(&self, conditions: Vec<WaitCondition<'_>>) -> Result<()> {
+        send_request_sync!(
+            self,
+            SyncOpcode::Await,
+            AwaitRequest {
+                wait_list: conditions
+                    .into_iter()
+                    .map(|x| sync::WaitCondition {
+                        trigger: x.trigger.to_protocol(),
+                        event_threshold: x.event_threshold,
+                    })
+                    .collect(),
+            }
+        );
+        Ok(())
+    }
+}
+
+impl<'a> Counter<'a> {
+    pub async fn query(self) -> Result<i64> {
+        let reply = send_request_sync!(
+            self.connection,
+            SyncOpcode::QueryCounter,
+            QueryCounterResponse,
+            QueryCounterRequest {
+                counter: self.handle,
+            }
+        );
+        Ok(reply.counter_value)
+    }
+
+    pub async fn set(self, value: i64) -> Result<()> {
+        send_request_sync!(
+            self.connection,
+            SyncOpcode::SetCounter,
+            SetCounterRequest {
+                counter: self.handle,
+                value: value,
+            }
+        );
+        Ok(())
+    }
+
+    /// Adds `amount` to the counter, which fails if it overflows
+    pub async fn change(self, amount: i64) -> Result<()> {
+        send_request_sync!(
+            self.connection,
+            SyncOpcode::ChangeCounter,
+            ChangeCounterRequest {
+                counter: self.handle,
+                amount: amount,
+            }
+        );
+        Ok(())
+    }
+
+    /// System counters can't be destroyed
+    pub async fn destroy(self) -> Result<()> {
+        send_request_sync!(
+            self.connection,
+            SyncOpcode::DestroyCounter,
+            DestroyCounterRequest {
+                counter: self.handle,
+            }
+        );
+        Ok(())
+    }
+}
+
+impl<'a> Resource<'a> for Counter<'a> {
+    fn x11_handle(&self) -> u32 {
+        self.handle
+    }
+
+    fn from_x11_handle(connection: &'a X11Connection, handle: u32) -> Self {
+        Self {
+            connection,
+            handle,
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::coding::sync::{
+    AwaitFenceRequest, CreateFenceRequest, DestroyFenceRequest, QueryFenceRequest, QueryFenceResponse, ResetFenceRequest, TriggerFenceRequest,
+};
+
+use super::*;
+
+/// A boolean that is triggered once rendering to a screen has completed, or by a client
+#[derive(Clone, Copy, derivative::Derivative)]
+#[derivative(Debug)]
+pub struct Fence<'a> {
+    pub(crate) handle: u32,
+    #[derivative(Debug = "ignore")]
+    pub(crate) connection: &'a X11Connection,
+}
+
+impl X11Connection {
+    /// Creates a fence on the screen of `drawable`
+    pub async fn create_fence(&self, drawable: impl Into<Drawable<'_>>, triggered: bool) -> Result<Fence<'_>> {
+        let fence = self.new_resource_id();
+        send_request_sync!(
+            self,
+            SyncOpcode::CreateFence,
+            CreateFenceRequest {
+                drawable: drawable.into().handle(),
+                fence: fence,
+                initially_triggered: triggered,
+            }
+        );
+
+        Ok(Fence {
+            handle: fence,
+            connection: self,
+        })
+    }
+
+    /// Blocks processing of this client's requests until any of `fences` is triggered
+    pub async fn await_fences(&self, fences: &[Fence<'_>]) -> Result<()> {
+        send_request_sync!(
+            self,
+            SyncOpcode::AwaitFence,
+            AwaitFenceRequest {
+                fence_list: fences.iter().map(|x| x.handle).collect(),
+            }
+        );
+        Ok(())
+    }
+}
+
+impl<'a> Fence<'a> {
+    /// Triggers the fence once all rendering requested before it has completed
+    pub async fn trigger(self) -> Result<()> {
+        send_request_sync!(
+            self.connection,
+            SyncOpcode::TriggerFence,
+            TriggerFenceRequest {
+                fence: self.handle,
+            }
+        );
+        Ok(())
+    }
+
+    /// Untriggers the fence, which must be triggered
+    pub async fn reset(self) -> Result<()> {
+        send_request_sync!(
+            self.connection,
+            SyncOpcode::ResetFence,
+            ResetFenceRequest {
+                fence: self.handle,
+            }
+        );
+        Ok(())
+    }
+
+    pub async fn is_triggered(self) -> Result<bool> {
+        let reply = send_request_sync!(
+            self.connection,
+            SyncOpcode::QueryFence,
+            QueryFenceResponse,
+            QueryFenceRequest {
+                fence: self.handle,
+            }
+        );
+        Ok(reply.triggered)
+    }
+
+    pub async fn destroy(self) -> Result<()> {
+        send_request_sync!(
+            self.connection,
+            SyncOpcode::DestroyFence,
+            DestroyFenceRequest {
+                fence: self.handle,
+            }
+        );
+        Ok(())
+    }
+}
+
+impl<'a> Resource<'a> for Fence<'a> {
+    fn x11_handle(&self) -> u32 {
+        self.handle
+    }
+
+    fn from_x11_handle(connection: &'a X11Connection, handle: u32) -> Self {
+        Self {
+            connection,
+            handle,
+        }
+    }
+}
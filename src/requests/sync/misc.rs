@@ -0,0 +1,106 @@
+use crate::{
+    coding::sync::{
+        GetPriorityRequest, GetPriorityResponse, InitializeRequest, InitializeResponse, ListSystemCountersRequest, ListSystemCountersResponse,
+        SetPriorityRequest,
+    },
+    net::{ExtInfo, Extension},
+};
+
+use super::*;
+
+/// Milliseconds since the server started
+pub const SERVERTIME_COUNTER: &str = "SERVERTIME";
+/// Milliseconds since the last user input
+pub const IDLETIME_COUNTER: &str = "IDLETIME";
+
+/// Counter maintained by the server, see [`X11Connection::list_system_counters`]
+#[derive(Debug, Clone)]
+pub struct SystemCounter<'a> {
+    pub counter: Counter<'a>,
+    /// approximate number of units the counter changes by per second
+    pub resolution: i64,
+    pub name: String,
+}
+
+impl X11Connection {
+    pub(crate) async fn enable_sync(&self) -> Result<()> {
+        // query_extension
+        let queried = self.query_extension(SYNC_EXT_NAME).await?;
+        ensure!(queried.present, "sync missing on x11 server");
+        self.0.registered_extensions.insert(
+            SYNC_EXT_NAME.to_string(),
+            ExtInfo {
+                extension: Extension::Sync,
+                major_opcode: queried.major_opcode,
+                event_start: queried.first_event,
+                error_start: queried.first_error,
+                event_count: SYNC_EVENT_COUNT,
+                version: None,
+            },
+        );
+
+        // enable extension
+        let reply = send_request_ext!(
+            self,
+            queried.major_opcode,
+            SyncOpcode::Initialize,
+            InitializeResponse,
+            InitializeRequest {
+                desired_major_version: 3,
+                desired_minor_version: 1,
+            }
+        );
+        if reply.major_version != 3 {
+            bail!("unsupported sync version on server: {}.{}", reply.major_version, reply.minor_version);
+        }
+        self.set_ext_version(SYNC_EXT_NAME, reply.major_version, reply.minor_version);
+        Ok(())
+    }
+
+    pub async fn list_system_counters(&self) -> Result<Vec<SystemCounter<'_>>> {
+        let reply = send_request_sync!(self, SyncOpcode::ListSystemCounters, ListSystemCountersResponse, ListSystemCountersRequest {});
+
+        Ok(reply
+            .counters
+            .into_iter()
+            .map(|x| SystemCounter {
+                counter: Counter {
+                    handle: x.counter,
+                    connection: self,
+                },
+                resolution: x.resolution,
+                name: x.name,
+            })
+            .collect())
+    }
+
+    /// Finds a system counter by name, such as [`SERVERTIME_COUNTER`] or [`IDLETIME_COUNTER`]
+    pub async fn get_system_counter(&self, name: &str) -> Result<Option<Counter<'_>>> {
+        Ok(self.list_system_counters().await?.into_iter().find(|x| x.name == name).map(|x| x.counter))
+    }
+
+    /// Sets the scheduling priority of this client, higher priorities are served first
+    pub async fn set_sync_priority(&self, priority: i32) -> Result<()> {
+        send_request_sync!(
+            self,
+            SyncOpcode::SetPriority,
+            SetPriorityRequest {
+                id: 0,
+                priority: priority,
+            }
+        );
+        Ok(())
+    }
+
+    pub async fn get_sync_priority(&self) -> Result<i32> {
+        let reply = send_request_sync!(
+            self,
+            SyncOpcode::GetPriority,
+            GetPriorityResponse,
+            GetPriorityRequest {
+                id: 0,
+            }
+        );
+        Ok(reply.priority)
+    }
+}
@@ -183,6 +183,42 @@ pub enum TextItem16<'a> {
     Font(Font<'a>),
 }
 
+// request header including the BIG-REQUESTS length field
+const REQUEST_HEADER_SIZE: usize = 8;
+
+// rewrites `CoordinateMode::Previous` points as absolute, so they can be split across requests
+fn to_origin_mode(points: &mut [Point]) {
+    for i in 1..points.len() {
+        points[i].x = points[i].x.wrapping_add(points[i - 1].x);
+        points[i].y = points[i].y.wrapping_add(points[i - 1].y);
+    }
+}
+
+impl X11Connection {
+    /// Number of `element_size` byte elements that fit into one request after `fixed_size` bytes of other fields
+    fn max_request_elements(&self, fixed_size: usize, element_size: usize) -> usize {
+        let body = (self.maximum_request_length() as usize * 4).saturating_sub(REQUEST_HEADER_SIZE + fixed_size);
+        body / element_size
+    }
+
+    /// Bytes per scanline and number of planes of image data in `format`
+    pub(crate) fn image_layout(&self, format: ImageFormat, width: u16, left_pad: u8, depth: u8) -> Option<(usize, usize)> {
+        let handshake = &self.0.handshake;
+        let (bits, scanline_pad, planes) = match format {
+            ImageFormat::ZPixmap => {
+                let format = handshake.pixmap_formats.iter().find(|x| x.depth == depth)?;
+                (width as usize * format.bits_per_pixel as usize, format.scanline_pad as usize, 1)
+            }
+            ImageFormat::XYPixmap => (left_pad as usize + width as usize, handshake.bitmap_format_scanline_pad as usize, depth as usize),
+            ImageFormat::Bitmap => (left_pad as usize + width as usize, handshake.bitmap_format_scanline_pad as usize, 1),
+        };
+        if scanline_pad == 0 {
+            return None;
+        }
+        Some(((bits + scanline_pad - 1) / scanline_pad * scanline_pad / 8, planes))
+    }
+}
+
 impl X11Connection {
     pub async fn create_gcontext(&self, drawable: impl Into<Drawable<'_>>, params: GContextParams<'_>) -> Result<GContext<'_>> {
         let gcontext = self.new_resource_id();
@@ -337,12 +373,30 @@ impl<'a> GContext<'a> {
         Ok(())
     }
 
-    pub async fn poly_point(self, drawable: impl Into<Drawable<'_>>, coordinate_mode: CoordinateMode, points: Vec<Point>) -> Result<()> {
+    pub async fn poly_point(self, drawable: impl Into<Drawable<'_>>, coordinate_mode: CoordinateMode, mut points: Vec<Point>) -> Result<()> {
+        let drawable = drawable.into().handle();
+        let max = self.connection.max_request_elements(8, 4).max(1);
+        if points.len() <= max {
+            return self.poly_point_raw(drawable, coordinate_mode, points).await;
+        }
+        if coordinate_mode == CoordinateMode::Previous {
+            to_origin_mode(&mut points);
+        }
+        self.connection
+            .send_split(
+                points
+                    .chunks(max)
+                    .map(|chunk| self.poly_point_raw(drawable, CoordinateMode::Origin, chunk.to_vec())),
+            )
+            .await
+    }
+
+    async fn poly_point_raw(self, drawable: u32, coordinate_mode: CoordinateMode, points: Vec<Point>) -> Result<()> {
         send_request!(
             self.connection,
             reserved coordinate_mode as u8,
             PolyPoint {
-                drawable: drawable.into().handle(),
+                drawable: drawable,
                 gcontext: self.handle,
                 points: points,
             }
@@ -350,12 +404,19 @@ impl<'a> GContext<'a> {
         Ok(())
     }
 
+    /// Unlike the other poly requests, lines are never split, as the points where the requests meet would be drawn twice
+    /// and with the cap style rather than the join style.
+    /// Lines that do not fit into one request fail with [`RequestTooLarge`](crate::net::RequestTooLarge).
     pub async fn poly_line(self, drawable: impl Into<Drawable<'_>>, coordinate_mode: CoordinateMode, points: Vec<Point>) -> Result<()> {
+        self.poly_line_raw(drawable.into().handle(), coordinate_mode, points).await
+    }
+
+    async fn poly_line_raw(self, drawable: u32, coordinate_mode: CoordinateMode, points: Vec<Point>) -> Result<()> {
         send_request!(
             self.connection,
             reserved coordinate_mode as u8,
             PolyLine {
-                drawable: drawable.into().handle(),
+                drawable: drawable,
                 gcontext: self.handle,
                 points: points,
             }
@@ -364,10 +425,21 @@ impl<'a> GContext<'a> {
     }
 
     pub async fn poly_segment(self, drawable: impl Into<Drawable<'_>>, segments: Vec<Segment>) -> Result<()> {
+        let drawable = drawable.into().handle();
+        let max = self.connection.max_request_elements(8, 8).max(1);
+        if segments.len() <= max {
+            return self.poly_segment_raw(drawable, segments).await;
+        }
+        self.connection
+            .send_split(segments.chunks(max).map(|chunk| self.poly_segment_raw(drawable, chunk.to_vec())))
+            .await
+    }
+
+    async fn poly_segment_raw(self, drawable: u32, segments: Vec<Segment>) -> Result<()> {
         send_request!(
             self.connection,
             PolySegment {
-                drawable: drawable.into().handle(),
+                drawable: drawable,
                 gcontext: self.handle,
                 segments: segments,
             }
@@ -376,10 +448,21 @@ impl<'a> GContext<'a> {
     }
 
     pub async fn poly_rectangle(self, drawable: impl Into<Drawable<'_>>, rectangles: Vec<Rectangle>) -> Result<()> {
+        let drawable = drawable.into().handle();
+        let max = self.connection.max_request_elements(8, 8).max(1);
+        if rectangles.len() <= max {
+            return self.poly_rectangle_raw(drawable, rectangles).await;
+        }
+        self.connection
+            .send_split(rectangles.chunks(max).map(|chunk| self.poly_rectangle_raw(drawable, chunk.to_vec())))
+            .await
+    }
+
+    async fn poly_rectangle_raw(self, drawable: u32, rectangles: Vec<Rectangle>) -> Result<()> {
         send_request!(
             self.connection,
             PolyRectangle {
-                drawable: drawable.into().handle(),
+                drawable: drawable,
                 gcontext: self.handle,
                 rectangles: rectangles.into_iter().map(Into::into).collect(),
             }
@@ -414,10 +497,21 @@ impl<'a> GContext<'a> {
     }
 
     pub async fn poly_fill_rectangle(self, drawable: impl Into<Drawable<'_>>, rectangles: Vec<Rectangle>) -> Result<()> {
+        let drawable = drawable.into().handle();
+        let max = self.connection.max_request_elements(8, 8).max(1);
+        if rectangles.len() <= max {
+            return self.poly_fill_rectangle_raw(drawable, rectangles).await;
+        }
+        self.connection
+            .send_split(rectangles.chunks(max).map(|chunk| self.poly_fill_rectangle_raw(drawable, chunk.to_vec())))
+            .await
+    }
+
+    async fn poly_fill_rectangle_raw(self, drawable: u32, rectangles: Vec<Rectangle>) -> Result<()> {
         send_request!(
             self.connection,
             PolyFillRectangle {
-                drawable: drawable.into().handle(),
+                drawable: drawable,
                 gcontext: self.handle,
                 rectangles: rectangles.into_iter().map(Into::into).collect(),
             }
@@ -437,6 +531,7 @@ impl<'a> GContext<'a> {
         Ok(())
     }
 
+    /// Sends image data, split into bands of rows if it does not fit into a single request
     pub async fn put_image(
         self,
         drawable: impl Into<Drawable<'_>>,
@@ -448,12 +543,49 @@ impl<'a> GContext<'a> {
         left_pad: u8,
         depth: u8,
         data: Vec<u8>,
+    ) -> Result<()> {
+        let drawable = drawable.into().handle();
+        let max_bytes = self.connection.max_request_elements(20, 1);
+        let (stride, planes) = match self.connection.image_layout(format, width, left_pad, depth) {
+            Some(layout) if data.len() > max_bytes && data.len() == layout.0 * layout.1 * height as usize => layout,
+            // fits, or we can't tell how to split it
+            _ => return self.put_image_raw(drawable, format, width, height, dst_x, dst_y, left_pad, depth, data).await,
+        };
+        let rows_per_band = max_bytes / (stride * planes);
+        if rows_per_band == 0 {
+            return self.put_image_raw(drawable, format, width, height, dst_x, dst_y, left_pad, depth, data).await;
+        }
+
+        let plane_size = stride * height as usize;
+        let bands = (0..height as usize).step_by(rows_per_band).map(|row| {
+            let rows = rows_per_band.min(height as usize - row);
+            let mut band = Vec::with_capacity(stride * rows * planes);
+            for plane in 0..planes {
+                let start = plane * plane_size + row * stride;
+                band.extend_from_slice(&data[start..start + rows * stride]);
+            }
+            self.put_image_raw(drawable, format, width, rows as u16, dst_x, dst_y.wrapping_add(row as i16), left_pad, depth, band)
+        });
+        self.connection.send_split(bands).await
+    }
+
+    async fn put_image_raw(
+        self,
+        drawable: u32,
+        format: ImageFormat,
+        width: u16,
+        height: u16,
+        dst_x: i16,
+        dst_y: i16,
+        left_pad: u8,
+        depth: u8,
+        data: Vec<u8>,
     ) -> Result<()> {
         send_request!(
             self.connection,
             reserved format as u8,
             PutImage {
-                drawable: drawable.into().handle(),
+                drawable: drawable,
                 gcontext: self.handle,
                 width: width,
                 height: height,
@@ -10,9 +10,14 @@ pub struct Pixmap<'a> {
 
 impl X11Connection {
     pub async fn create_pixmap(&self, depth: &Depth, drawable: impl Into<Drawable<'_>>, width: u16, height: u16) -> Result<Pixmap<'_>> {
+        self.create_pixmap_with_depth(depth.depth, drawable, width, height).await
+    }
+
+    /// Like [`X11Connection::create_pixmap`], for a depth given by number
+    pub async fn create_pixmap_with_depth(&self, depth: u8, drawable: impl Into<Drawable<'_>>, width: u16, height: u16) -> Result<Pixmap<'_>> {
         let pixmap = self.new_resource_id();
 
-        send_request!(self, reserved depth.depth, CreatePixmap {
+        send_request!(self, reserved depth, CreatePixmap {
             pixmap: pixmap,
             drawable: drawable.into().handle(),
             width: width,
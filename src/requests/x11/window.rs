@@ -331,12 +331,19 @@ impl X11Connection {
         })
     }
 
+    pub fn default_root_window(&self) -> Window<'_> {
+        Window {
+            handle: self.0.handshake.screens[self.0.default_screen].root_window,
+            connection: self,
+        }
+    }
+
     pub async fn create_window(&self, params: WindowParams<'_>) -> Result<Window<'_>> {
         let window = self.new_resource_id();
 
         send_request!(self, reserved params.depth, CreateWindow {
             window: window,
-            parent: params.parent.or_else(|| self.root_window(0)).ok_or_else(|| anyhow!("no screens for default parent"))?.handle,
+            parent: params.parent.unwrap_or_else(|| self.default_root_window()).handle,
             x: params.x,
             y: params.y,
             width: params.width,
@@ -4,9 +4,9 @@
 // };
 
 use crate::coding::xfixes::{
-    CopyRegionRequest, CreateRegionFromBitmapRequest, CreateRegionFromGCRequest, CreateRegionFromWindowRequest, CreateRegionRequest, DestroyRegionRequest,
-    ExpandRegionRequest, FetchRegionRequest, FetchRegionResponse, IntersectRegionRequest, InvertRegionRequest, RegionExtentsRequest, SetRegionRequest,
-    SubtractRegionRequest, TranslateRegionRequest, UnionRegionRequest,
+    CopyRegionRequest, CreateRegionFromBitmapRequest, CreateRegionFromGCRequest, CreateRegionFromPictureRequest, CreateRegionFromWindowRequest,
+    CreateRegionRequest, DestroyRegionRequest, ExpandRegionRequest, FetchRegionRequest, FetchRegionResponse, IntersectRegionRequest, InvertRegionRequest,
+    RegionExtentsRequest, SetRegionRequest, SubtractRegionRequest, TranslateRegionRequest, UnionRegionRequest,
 };
 
 use super::*;
@@ -94,19 +94,22 @@ impl X11Connection {
         Ok(region)
     }
 
-    //TODO: depends on render
-    // pub async fn create_region_from_picture(&self, picture: Picture<'_>) -> Result<Region<'_>> {
-    //     let region = Region {
-    //         handle: self.new_resource_id(),
-    //         connection: self,
-    //     };
-    //     send_request_xfixes!(self, XFOpcode::CreateRegionFromPicture, CreateRegionFromPictureRequest {
-    //         region: region.handle,
-    //         picture: picture.handle,
-    //     });
-
-    //     Ok(region)
-    // }
+    pub async fn create_region_from_picture(&self, picture: Picture<'_>) -> Result<Region<'_>> {
+        let region = Region {
+            handle: self.new_resource_id(),
+            connection: self,
+        };
+        send_request_xfixes!(
+            self,
+            XFOpcode::CreateRegionFromPicture,
+            CreateRegionFromPictureRequest {
+                region: region.handle,
+                picture: picture.handle,
+            }
+        );
+
+        Ok(region)
+    }
 }
 
 impl<'a> Region<'a> {
@@ -20,6 +20,7 @@ impl X11Connection {
                 event_start: queried.first_event,
                 error_start: queried.first_error,
                 event_count: 0,
+                version: None,
             },
         );
 
@@ -37,6 +38,7 @@ impl X11Connection {
         if reply.major_version != 1 {
             bail!("unsupported xge version on server: {}.{}", reply.major_version, reply.minor_version);
         }
+        self.set_ext_version(XGE_EXT_NAME, reply.major_version, reply.minor_version);
         Ok(())
     }
 }
@@ -0,0 +1,42 @@
+use crate::{
+    coding::xinerama::{QueryVersionRequest, QueryVersionResponse},
+    net::{ExtInfo, Extension},
+};
+
+use super::*;
+
+impl X11Connection {
+    pub(crate) async fn enable_xinerama(&self) -> Result<()> {
+        // query_extension
+        let queried = self.query_extension(XINERAMA_EXT_NAME).await?;
+        ensure!(queried.present, "xinerama missing on x11 server");
+        self.0.registered_extensions.insert(
+            XINERAMA_EXT_NAME.to_string(),
+            ExtInfo {
+                extension: Extension::Xinerama,
+                major_opcode: queried.major_opcode,
+                event_start: queried.first_event,
+                error_start: queried.first_error,
+                event_count: XINERAMA_EVENT_COUNT,
+                version: None,
+            },
+        );
+
+        // enable extension
+        let reply = send_request_ext!(
+            self,
+            queried.major_opcode,
+            XineramaOpcode::QueryVersion,
+            QueryVersionResponse,
+            QueryVersionRequest {
+                major_version: 1,
+                minor_version: 1,
+            }
+        );
+        if reply.major_version != 1 {
+            bail!("unsupported xinerama version on server: {}.{}", reply.major_version, reply.minor_version);
+        }
+        self.set_ext_version(XINERAMA_EXT_NAME, reply.major_version, reply.minor_version);
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+use crate::coding::xinerama::{IsActiveRequest, IsActiveResponse, QueryScreensRequest, QueryScreensResponse};
+
+use super::*;
+
+/// Area of the screen shown by one monitor
+#[derive(Clone, Copy, Debug)]
+pub struct Monitor {
+    pub x: i16,
+    pub y: i16,
+    pub width: u16,
+    pub height: u16,
+    /// a guess in the xinerama fallback when xrandr cannot name the primary output
+    pub primary: bool,
+}
+
+impl X11Connection {
+    pub async fn xinerama_is_active(&self) -> Result<bool> {
+        let reply = send_request_xinerama!(self, XineramaOpcode::IsActive, IsActiveResponse, IsActiveRequest {});
+        Ok(reply.state != 0)
+    }
+
+    /// Areas of the default screen shown by each physical screen
+    pub async fn xinerama_query_screens(&self) -> Result<Vec<Rectangle>> {
+        let reply = send_request_xinerama!(self, XineramaOpcode::QueryScreens, QueryScreensResponse, QueryScreensRequest {});
+
+        Ok(reply
+            .screens
+            .into_iter()
+            .map(|x| Rectangle {
+                x: x.x,
+                y: x.y,
+                width: x.width,
+                height: x.height,
+            })
+            .collect())
+    }
+
+    /// Area of the default screen shown by xrandr's primary output, if it has one and is enabled
+    async fn xrandr_primary_area(&self) -> Result<Option<Rectangle>> {
+        let output = self.default_screen().root.get_output_primary().await?;
+        if output.handle == 0 {
+            return Ok(None);
+        }
+        let crtc = output.get_info(Timestamp::CURRENT_TIME).await?.crtc;
+        if crtc.handle == 0 {
+            return Ok(None);
+        }
+        let info = crtc.get_info(Timestamp::CURRENT_TIME).await?;
+        Ok(Some(Rectangle {
+            x: info.x,
+            y: info.y,
+            width: info.width,
+            height: info.height,
+        }))
+    }
+
+    /// Monitors of the default screen, from xrandr if it supports monitors, otherwise from xinerama, otherwise the whole screen
+    pub async fn monitors(&self) -> Result<Vec<Monitor>> {
+        let screen = self.default_screen();
+
+        if self.get_ext_info(XRANDR_EXT_NAME).is_some() {
+            match screen.root.get_monitors_active().await {
+                Ok((_, monitors)) if !monitors.is_empty() => {
+                    return Ok(monitors
+                        .into_iter()
+                        .map(|x| Monitor {
+                            x: x.x,
+                            y: x.y,
+                            width: x.width,
+                            height: x.height,
+                            primary: x.primary,
+                        })
+                        .collect());
+                }
+                Ok(_) => (),
+                Err(e) => debug!("failed to get xrandr monitors, falling back to xinerama: {:?}", e),
+            }
+        }
+
+        if self.get_ext_info(XINERAMA_EXT_NAME).is_some() {
+            let screens = match self.xinerama_is_active().await {
+                Ok(true) => self.xinerama_query_screens().await,
+                Ok(false) => Ok(vec![]),
+                Err(e) => Err(e),
+            };
+            match screens {
+                Ok(screens) if !screens.is_empty() => {
+                    let primary_area = match self.get_ext_info(XRANDR_EXT_NAME) {
+                        Some(_) => self.xrandr_primary_area().await.unwrap_or_else(|e| {
+                            debug!("failed to get the xrandr primary output: {:?}", e);
+                            None
+                        }),
+                        None => None,
+                    };
+                    return Ok(screens
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, x)| Monitor {
+                            x: x.x,
+                            y: x.y,
+                            width: x.width,
+                            height: x.height,
+                            // xinerama has no notion of a primary screen, so without xrandr this is a guess: servers usually list it first
+                            primary: match &primary_area {
+                                Some(area) => area.x == x.x && area.y == x.y && area.width == x.width && area.height == x.height,
+                                None => i == 0,
+                            },
+                        })
+                        .collect());
+                }
+                Ok(_) => (),
+                Err(e) => debug!("failed to get xinerama screens, falling back to the core screen: {:?}", e),
+            }
+        }
+
+        Ok(vec![Monitor {
+            x: 0,
+            y: 0,
+            width: screen.width_in_pixels,
+            height: screen.height_in_pixels,
+            primary: true,
+        }])
+    }
+}
@@ -118,6 +118,25 @@ pub enum PassiveUngrab {
 }
 
 impl<'a> Device<'a> {
+    pub(crate) fn from_xi1_id(connection: &'a X11Connection, id: u8) -> Self {
+        Device {
+            id: match id {
+                0 => DeviceId::All,
+                1 => DeviceId::AllMaster,
+                id => DeviceId::Device(id as u16),
+            },
+            connection,
+        }
+    }
+
+    pub(crate) fn raw_id(&self) -> u16 {
+        match self.id {
+            DeviceId::All => 0,
+            DeviceId::AllMaster => 1,
+            DeviceId::Device(id) => id,
+        }
+    }
+
     pub async fn query(self) -> Result<Vec<DeviceData<'a>>> {
         let reply = send_request_xinput!(
             self.connection,
@@ -17,7 +17,8 @@ impl X11Connection {
                 major_opcode: queried.major_opcode,
                 event_start: queried.first_event,
                 error_start: queried.first_error,
-                event_count: 0,
+                event_count: XINPUT_EVENT_COUNT,
+                version: None,
             },
         );
 
@@ -35,6 +36,7 @@ impl X11Connection {
         if reply.major_version != 2 {
             bail!("unsupported xinput version on server: {}.{}", reply.major_version, reply.minor_version);
         }
+        self.set_ext_version(XINPUT_EXT_NAME, reply.major_version, reply.minor_version);
         Ok(())
     }
 }
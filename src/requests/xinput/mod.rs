@@ -4,6 +4,8 @@ use crate::coding::RequestBody;
 pub use fixed::types::{I16F16, I32F32};
 
 pub const XINPUT_EXT_NAME: &str = "XInputExtension";
+// events from xinput 1.x, xinput 2 events are generic events
+const XINPUT_EVENT_COUNT: u8 = 17;
 
 macro_rules! send_request_xinput {
     ($self_:expr, $opcode:expr, $name:ident { $($key:ident: $value:expr,)* }) => {
@@ -87,3 +89,6 @@ pub use property::*;
 
 mod class;
 pub use class::*;
+
+mod xi1;
+pub use xi1::*;
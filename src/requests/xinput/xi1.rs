@@ -0,0 +1,351 @@
+use crate::coding::xinput1::{
+    self, ChangeDeviceControlRequest, ChangeDeviceControlResponse, ChangeDeviceData, CloseDeviceRequest, GetDeviceMotionEventsRequest,
+    GetDeviceMotionEventsResponse, InputClassInfo, InputInfoData, ListInputDevicesRequest, ListInputDevicesResponse, ListInputDevicesResponseAdd,
+    OpenDeviceRequest, OpenDeviceResponse, SelectExtensionEventRequest, XI1Opcode,
+};
+pub use crate::coding::xinput1::{DeviceControl, DeviceUse, InputClass, XI1EventCode};
+
+use super::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct XI1AxisInfo {
+    pub resolution: u32,
+    pub minimum: i32,
+    pub maximum: i32,
+}
+
+#[derive(Clone, Debug)]
+pub enum XI1InputInfo {
+    Key { min_keycode: u8, max_keycode: u8, num_keys: u16 },
+    Button { num_buttons: u16 },
+    Valuator { absolute: bool, motion_size: u32, axes: Vec<XI1AxisInfo> },
+}
+
+#[derive(Clone, Debug)]
+pub struct XI1DeviceInfo<'a> {
+    pub device: Device<'a>,
+    pub name: String,
+    /// type of device, such as `TABLET` or `MOUSE`
+    pub device_type: Option<Atom>,
+    pub device_use: DeviceUse,
+    pub classes: Vec<XI1InputInfo>,
+}
+
+/// An event class, as selected by [`Window::xi1_select_events`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct XI1EventClass(pub u32);
+
+impl XI1EventClass {
+    /// Selects `DevicePresenceNotify` events, which aren't specific to a device
+    pub const DEVICE_PRESENCE: XI1EventClass = XI1EventClass(0x10000);
+}
+
+/// A device opened for XI1 requests and event selection, closed with [`XI1OpenDevice::close`]
+#[derive(Clone, Debug)]
+pub struct XI1OpenDevice<'a> {
+    pub device: Device<'a>,
+    classes: Vec<InputClassInfo>,
+}
+
+#[derive(Clone, Debug)]
+pub struct XI1MotionEvent {
+    pub time: Timestamp,
+    /// values of each valuator of the device
+    pub values: Vec<i32>,
+}
+
+#[derive(Clone, Debug)]
+pub struct XI1MotionEvents {
+    pub absolute: bool,
+    pub events: Vec<XI1MotionEvent>,
+}
+
+#[derive(Clone, Debug)]
+pub enum XI1DeviceControl {
+    /// sets the resolution of valuators starting at `first_valuator`
+    Resolution {
+        first_valuator: u8,
+        values: Vec<u32>,
+    },
+    AbsoluteCalibration {
+        min_x: i32,
+        max_x: i32,
+        min_y: i32,
+        max_y: i32,
+        flip_x: u32,
+        flip_y: u32,
+        rotation: u32,
+        button_threshold: u32,
+    },
+    Core {
+        status: u8,
+    },
+    Enable(bool),
+    AbsoluteArea {
+        offset_x: u32,
+        offset_y: u32,
+        width: i32,
+        height: i32,
+        screen: i32,
+        following: u32,
+    },
+}
+
+impl XI1DeviceControl {
+    fn encode(self) -> (DeviceControl, ChangeDeviceData) {
+        match self {
+            XI1DeviceControl::Resolution {
+                first_valuator,
+                values,
+            } => (
+                DeviceControl::Resolution,
+                ChangeDeviceData::Resolution {
+                    first_valuator,
+                    num_valuators: 0,
+                    resolution_values: values,
+                },
+            ),
+            XI1DeviceControl::AbsoluteCalibration {
+                min_x,
+                max_x,
+                min_y,
+                max_y,
+                flip_x,
+                flip_y,
+                rotation,
+                button_threshold,
+            } => (
+                DeviceControl::AbsoluteCalibration,
+                ChangeDeviceData::AbsoluteCalibration {
+                    min_x,
+                    max_x,
+                    min_y,
+                    max_y,
+                    flip_x,
+                    flip_y,
+                    rotation,
+                    button_threshold,
+                },
+            ),
+            XI1DeviceControl::Core {
+                status,
+            } => (
+                DeviceControl::Core,
+                ChangeDeviceData::Core {
+                    status,
+                },
+            ),
+            XI1DeviceControl::Enable(enable) => (
+                DeviceControl::Enable,
+                ChangeDeviceData::Enable {
+                    enable: enable as u8,
+                },
+            ),
+            XI1DeviceControl::AbsoluteArea {
+                offset_x,
+                offset_y,
+                width,
+                height,
+                screen,
+                following,
+            } => (
+                DeviceControl::AbsoluteArea,
+                ChangeDeviceData::AbsoluteArea {
+                    offset_x,
+                    offset_y,
+                    width,
+                    height,
+                    screen,
+                    following,
+                },
+            ),
+        }
+    }
+}
+
+impl X11Connection {
+    /// Lists input devices with their XI1 classes
+    pub async fn xi1_list_input_devices(&self) -> Result<Vec<XI1DeviceInfo<'_>>> {
+        let reply = send_request_xinput!(self, XI1Opcode::ListInputDevices, ListInputDevicesResponse, ListInputDevicesRequest {}).into_inner();
+        let info_count = reply.devices.iter().map(|x| x.num_class_info as u64).sum();
+        let add = ListInputDevicesResponseAdd::decode_sync(&mut &reply.add[..], info_count, reply.devices.len() as u64)?;
+
+        let mut infos = add.infos.into_iter();
+        let mut out = vec![];
+        for (device, name) in reply.devices.into_iter().zip(add.names) {
+            let mut classes = vec![];
+            for info in infos.by_ref().take(device.num_class_info as usize) {
+                classes.push(match info.data {
+                    InputInfoData::Key {
+                        min_keycode,
+                        max_keycode,
+                        num_keys,
+                    } => XI1InputInfo::Key {
+                        min_keycode,
+                        max_keycode,
+                        num_keys,
+                    },
+                    InputInfoData::Button {
+                        num_buttons,
+                    } => XI1InputInfo::Button {
+                        num_buttons,
+                    },
+                    InputInfoData::Valuator {
+                        mode,
+                        motion_size,
+                        axes,
+                        ..
+                    } => XI1InputInfo::Valuator {
+                        absolute: mode == xinput1::ValuatorMode::Absolute,
+                        motion_size,
+                        axes: axes
+                            .into_iter()
+                            .map(|x| XI1AxisInfo {
+                                resolution: x.resolution,
+                                minimum: x.minimum,
+                                maximum: x.maximum,
+                            })
+                            .collect(),
+                    },
+                });
+            }
+            let device_type = match device.device_type_atom {
+                0 => None,
+                atom => Some(self.get_atom_name(atom).await?),
+            };
+
+            out.push(XI1DeviceInfo {
+                device: Device::from_xi1_id(self, device.device_id),
+                name: name.string,
+                device_type,
+                device_use: device.device_use,
+                classes,
+            });
+        }
+        Ok(out)
+    }
+}
+
+impl<'a> Device<'a> {
+    /// Opens the device for XI1 requests, returning the classes of events it can report
+    pub async fn xi1_open(self) -> Result<XI1OpenDevice<'a>> {
+        let reply = send_request_xinput!(
+            self.connection,
+            XI1Opcode::OpenDevice,
+            OpenDeviceResponse,
+            OpenDeviceRequest {
+                device_id: self.xi1_id()?,
+            }
+        );
+
+        Ok(XI1OpenDevice {
+            device: self,
+            classes: reply.into_inner().classes,
+        })
+    }
+
+    /// Motion history of the device between `start` and `stop`
+    pub async fn xi1_motion_events(self, start: Timestamp, stop: Timestamp) -> Result<XI1MotionEvents> {
+        let reply = send_request_xinput!(
+            self.connection,
+            XI1Opcode::GetDeviceMotionEvents,
+            GetDeviceMotionEventsResponse,
+            GetDeviceMotionEventsRequest {
+                start_time: start.0,
+                stop_time: stop.0,
+                device_id: self.xi1_id()?,
+            }
+        )
+        .into_inner();
+
+        Ok(XI1MotionEvents {
+            absolute: reply.device_mode == xinput1::ValuatorMode::Absolute,
+            events: reply
+                .events
+                .into_iter()
+                .map(|x| XI1MotionEvent {
+                    time: Timestamp(x.time),
+                    values: x.values,
+                })
+                .collect(),
+        })
+    }
+
+    /// Changes a control of the device, returns false if the device was busy
+    pub async fn xi1_change_control(self, control: XI1DeviceControl) -> Result<bool> {
+        let (control, change) = control.encode();
+        let reply = send_request_xinput!(
+            self.connection,
+            XI1Opcode::ChangeDeviceControl,
+            ChangeDeviceControlResponse,
+            ChangeDeviceControlRequest {
+                control: control,
+                device_id: self.xi1_id()?,
+                control_id: control,
+                len: 0,
+                change: change,
+            }
+        );
+        Ok(reply.status == xinput1::GrabStatus::Success)
+    }
+
+    fn xi1_id(&self) -> Result<u8> {
+        let id = self.raw_id();
+        if !(2..=0xff).contains(&id) {
+            bail!("device {} cannot be used with xi1 requests", id);
+        }
+        Ok(id as u8)
+    }
+}
+
+impl<'a> XI1OpenDevice<'a> {
+    /// The class selecting `event` from this device, if the device can report it
+    pub fn event_class(&self, event: XI1EventCode) -> Option<XI1EventClass> {
+        let (class, offset) = match event {
+            XI1EventCode::DeviceKeyPress => (InputClass::Key, 0),
+            XI1EventCode::DeviceKeyRelease => (InputClass::Key, 1),
+            XI1EventCode::DeviceButtonPress => (InputClass::Button, 0),
+            XI1EventCode::DeviceButtonRelease => (InputClass::Button, 1),
+            XI1EventCode::DeviceMotionNotify => (InputClass::Valuator, 0),
+            XI1EventCode::DeviceFocusIn => (InputClass::Focus, 0),
+            XI1EventCode::DeviceFocusOut => (InputClass::Focus, 1),
+            XI1EventCode::ProximityIn => (InputClass::Proximity, 0),
+            XI1EventCode::ProximityOut => (InputClass::Proximity, 1),
+            XI1EventCode::DeviceStateNotify => (InputClass::Other, 0),
+            XI1EventCode::DeviceMappingNotify => (InputClass::Other, 1),
+            XI1EventCode::ChangeDeviceNotify => (InputClass::Other, 2),
+            XI1EventCode::DevicePropertyNotify => (InputClass::Other, 6),
+            // sent along with other events, or selected with `XI1EventClass::DEVICE_PRESENCE`
+            _ => return None,
+        };
+        let info = self.classes.iter().find(|x| x.class == class)?;
+        let device_id = self.device.raw_id() as u32;
+        Some(XI1EventClass((device_id << 8) | (info.event_type_base + offset) as u32))
+    }
+
+    pub async fn close(self) -> Result<()> {
+        send_request_xinput!(
+            self.device.connection,
+            XI1Opcode::CloseDevice,
+            CloseDeviceRequest {
+                device_id: self.device.xi1_id()?,
+            }
+        );
+        Ok(())
+    }
+}
+
+impl<'a> Window<'a> {
+    /// Selects XI1 events on this window, replacing the classes previously selected by this client
+    pub async fn xi1_select_events(self, classes: &[XI1EventClass]) -> Result<()> {
+        send_request_xinput!(
+            self.connection,
+            XI1Opcode::SelectExtensionEvent,
+            SelectExtensionEventRequest {
+                window: self.handle,
+                event_classes: classes.iter().map(|x| x.0).collect(),
+            }
+        );
+        Ok(())
+    }
+}
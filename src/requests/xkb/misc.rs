@@ -18,6 +18,7 @@ impl X11Connection {
                 event_start: queried.first_event,
                 error_start: queried.first_error,
                 event_count: XKB_EVENT_COUNT,
+                version: None,
             },
         );
 
@@ -35,6 +36,7 @@ impl X11Connection {
         if reply.reserved == 0 || reply.server_major != 1 {
             bail!("unsupported xkb version on server: {}.{}", reply.server_major, reply.server_minor);
         }
+        self.set_ext_version(XKB_EXT_NAME, reply.server_major, reply.server_minor);
         Ok(())
     }
 }
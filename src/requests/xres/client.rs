@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+pub use crate::coding::xres::ClientIdMask;
+use crate::coding::xres::{
+    ClientIdSpec, QueryClientIdsRequest, QueryClientIdsResponse, QueryClientPixmapBytesRequest, QueryClientPixmapBytesResponse, QueryClientResourcesRequest,
+    QueryClientResourcesResponse, QueryClientsRequest, QueryClientsResponse,
+};
+
+use super::*;
+
+/// A client connected to the x11 server, identified by the base of the resource ids it allocates.
+/// Can be passed to [`X11Connection::kill_client`].
+#[derive(Clone, Copy, derivative::Derivative)]
+#[derivative(Debug)]
+pub struct XResClient<'a> {
+    pub(crate) handle: u32,
+    pub mask: u32,
+    #[derivative(Debug = "ignore")]
+    pub(crate) connection: &'a X11Connection,
+}
+
+#[derive(Debug, Clone)]
+pub struct XResResourceCount {
+    pub resource_type: Atom,
+    pub count: u32,
+}
+
+/// Identifiers of a client, as selected by `mask`
+#[derive(Debug, Clone)]
+pub struct XResClientId {
+    /// resource id base of the client
+    pub client: u32,
+    pub mask: ClientIdMask,
+    pub value: Vec<u32>,
+}
+
+impl X11Connection {
+    pub async fn xres_query_clients(&self) -> Result<Vec<XResClient<'_>>> {
+        let reply = send_request_xres!(self, XResOpcode::QueryClients, QueryClientsResponse, QueryClientsRequest {});
+
+        Ok(reply
+            .into_inner()
+            .clients
+            .into_iter()
+            .map(|x| XResClient {
+                handle: x.resource_base,
+                mask: x.resource_mask,
+                connection: self,
+            })
+            .collect())
+    }
+
+    /// Identifiers of the clients owning each of `clients`, or of all clients if `clients` is `None`.
+    /// Each selected identifier is returned separately. Requires X-Resource 1.2.
+    pub async fn xres_query_client_ids(&self, clients: Option<&[XResClient<'_>]>, mask: ClientIdMask) -> Result<Vec<XResClientId>> {
+        self.require_ext_version(XRES_EXT_NAME, 1, 2, "QueryClientIds")?;
+        let specs = match clients {
+            Some(clients) => clients
+                .iter()
+                .map(|x| ClientIdSpec {
+                    client: x.handle,
+                    mask,
+                })
+                .collect(),
+            None => vec![ClientIdSpec {
+                client: 0,
+                mask,
+            }],
+        };
+        let reply = send_request_xres!(
+            self,
+            XResOpcode::QueryClientIds,
+            QueryClientIdsResponse,
+            QueryClientIdsRequest {
+                specs: specs,
+            }
+        );
+
+        Ok(reply
+            .into_inner()
+            .ids
+            .into_iter()
+            .map(|x| XResClientId {
+                client: x.spec.client,
+                mask: x.spec.mask,
+                value: x.value,
+            })
+            .collect())
+    }
+
+    /// Process ids of all local clients whose pid the server knows, keyed by their resource id base.
+    /// Requires X-Resource 1.2.
+    pub async fn xres_client_pids(&self) -> Result<HashMap<u32, u32>> {
+        let ids = self.xres_query_client_ids(None, ClientIdMask::LOCAL_CLIENT_PID).await?;
+
+        Ok(ids
+            .into_iter()
+            .filter(|x| x.mask.0 & ClientIdMask::LOCAL_CLIENT_PID.0 != 0)
+            .filter_map(|x| Some((x.client, *x.value.first()?)))
+            .collect())
+    }
+}
+
+impl<'a> XResClient<'a> {
+    /// Resource id base of the client
+    pub fn base(&self) -> u32 {
+        self.handle
+    }
+
+    /// Whether `xid` was allocated by this client
+    pub fn owns(&self, xid: u32) -> bool {
+        xid & !self.mask == self.handle
+    }
+
+    /// Number of resources of each type the client owns
+    pub async fn resources(self) -> Result<Vec<XResResourceCount>> {
+        let reply = send_request_xres!(
+            self.connection,
+            XResOpcode::QueryClientResources,
+            QueryClientResourcesResponse,
+            QueryClientResourcesRequest {
+                xid: self.handle,
+            }
+        );
+        let types = reply.into_inner().types;
+        let atoms = self.connection.get_all_atoms(types.iter().map(|x| x.resource_type)).await?;
+
+        Ok(atoms
+            .into_iter()
+            .zip(types)
+            .map(|(resource_type, x)| XResResourceCount {
+                resource_type,
+                count: x.count,
+            })
+            .collect())
+    }
+
+    /// Bytes of pixmap memory attributed to the client, shared pixmaps are divided among their users
+    pub async fn pixmap_bytes(self) -> Result<u64> {
+        let reply = send_request_xres!(
+            self.connection,
+            XResOpcode::QueryClientPixmapBytes,
+            QueryClientPixmapBytesResponse,
+            QueryClientPixmapBytesRequest {
+                xid: self.handle,
+            }
+        );
+        Ok(((reply.bytes_overflow as u64) << 32) | reply.bytes as u64)
+    }
+
+    /// Process id of the client, if it is local and the server could determine it.
+    /// Requires X-Resource 1.2.
+    pub async fn pid(self) -> Result<Option<u32>> {
+        let ids = self.connection.xres_query_client_ids(Some(&[self]), ClientIdMask::LOCAL_CLIENT_PID).await?;
+
+        Ok(ids
+            .into_iter()
+            .find(|x| x.mask.0 & ClientIdMask::LOCAL_CLIENT_PID.0 != 0)
+            .and_then(|x| x.value.first().copied()))
+    }
+}
+
+impl<'a> Resource<'a> for XResClient<'a> {
+    fn x11_handle(&self) -> u32 {
+        self.handle
+    }
+
+    fn from_x11_handle(connection: &'a X11Connection, handle: u32) -> Self {
+        Self {
+            connection,
+            handle,
+            mask: 0,
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{
+    coding::xres::{QueryVersionRequest, QueryVersionResponse},
+    net::{ExtInfo, Extension},
+};
+
+use super::*;
+
+impl X11Connection {
+    pub(crate) async fn enable_xres(&self) -> Result<()> {
+        // query_extension
+        let queried = self.query_extension(XRES_EXT_NAME).await?;
+        ensure!(queried.present, "x-resource missing on x11 server");
+        self.0.registered_extensions.insert(
+            XRES_EXT_NAME.to_string(),
+            ExtInfo {
+                extension: Extension::XRes,
+                major_opcode: queried.major_opcode,
+                event_start: queried.first_event,
+                error_start: queried.first_error,
+                event_count: XRES_EVENT_COUNT,
+                version: None,
+            },
+        );
+
+        // enable extension
+        let reply = send_request_ext!(
+            self,
+            queried.major_opcode,
+            XResOpcode::QueryVersion,
+            QueryVersionResponse,
+            QueryVersionRequest {
+                client_major_version: 1,
+                client_minor_version: 2,
+            }
+        );
+        if reply.server_major_version != 1 {
+            bail!("unsupported x-resource version on server: {}.{}", reply.server_major_version, reply.server_minor_version);
+        }
+        self.set_ext_version(XRES_EXT_NAME, reply.server_major_version, reply.server_minor_version);
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+use crate::coding::xtest::{CompareCursorRequest, CompareCursorResponse, FakeDeviceValuator, FakeEventType, FakeInputRequest, GrabControlRequest};
+
+use super::*;
+
+const SHIFT_L: Keysym = Keysym(0xffe1);
+// set on the device id of a device event followed by valuator events
+const MORE_EVENTS: u8 = 0x80;
+
+/// Input synthesized with XTEST, which is processed as if it came from a real device
+#[derive(Debug, Clone, Copy)]
+pub enum FakeInput<'a> {
+    KeyPress(u8),
+    KeyRelease(u8),
+    ButtonPress(u8),
+    ButtonRelease(u8),
+    Motion {
+        /// screen to move the pointer to, the current one if `None`
+        root: Option<Window<'a>>,
+        x: i16,
+        y: i16,
+        /// whether `x` and `y` are relative to the current position
+        relative: bool,
+    },
+}
+
+/// Cursor to compare a window's cursor to
+#[derive(Debug, Clone, Copy)]
+pub enum XTestCursor<'a> {
+    None,
+    /// the cursor currently displayed
+    Current,
+    Some(Cursor<'a>),
+}
+
+impl X11Connection {
+    async fn send_fake_input(&self, event_type: u8, device_id: u8, input: FakeInput<'_>, delay: u32, valuators: Vec<FakeDeviceValuator>) -> Result<()> {
+        let (detail, root_window, root_x, root_y) = match input {
+            FakeInput::KeyPress(detail) | FakeInput::KeyRelease(detail) | FakeInput::ButtonPress(detail) | FakeInput::ButtonRelease(detail) => {
+                (detail, 0, 0, 0)
+            }
+            FakeInput::Motion {
+                root,
+                x,
+                y,
+                relative,
+            } => (relative as u8, root.map(|x| x.handle).unwrap_or(0), x, y),
+        };
+        send_request_xtest!(
+            self,
+            XTestOpcode::FakeInput,
+            FakeInputRequest {
+                event_type: event_type,
+                detail: detail,
+                time: delay,
+                root_window: root_window,
+                root_x: root_x,
+                root_y: root_y,
+                device_id: if valuators.is_empty() { device_id } else { device_id | MORE_EVENTS },
+                valuators: valuators,
+            }
+        );
+        Ok(())
+    }
+
+    /// Injects `input` through the core devices after `delay` milliseconds
+    pub async fn fake_input(&self, input: FakeInput<'_>, delay: u32) -> Result<()> {
+        let event_type = match input {
+            FakeInput::KeyPress(_) => FakeEventType::KeyPress,
+            FakeInput::KeyRelease(_) => FakeEventType::KeyRelease,
+            FakeInput::ButtonPress(_) => FakeEventType::ButtonPress,
+            FakeInput::ButtonRelease(_) => FakeEventType::ButtonRelease,
+            FakeInput::Motion {
+                ..
+            } => FakeEventType::MotionNotify,
+        };
+        self.send_fake_input(event_type as u8, 0, input, delay, vec![]).await
+    }
+
+    /// Injects `input` through an xinput slave device after `delay` milliseconds, setting the device's valuators
+    /// starting at `first_valuator` to `valuators`.
+    /// Motion must set at least one valuator, its `root`, `x` and `y` are ignored and `relative` applies to the valuators.
+    pub async fn fake_device_input(&self, device: Device<'_>, input: FakeInput<'_>, first_valuator: u8, valuators: &[i32], delay: u32) -> Result<()> {
+        let event_start = self
+            .get_ext_info(XINPUT_EXT_NAME)
+            .ok_or_else(|| anyhow!("xinput is required to fake device input"))?
+            .event_start;
+        let event_code = match input {
+            FakeInput::KeyPress(_) => XI1EventCode::DeviceKeyPress,
+            FakeInput::KeyRelease(_) => XI1EventCode::DeviceKeyRelease,
+            FakeInput::ButtonPress(_) => XI1EventCode::DeviceButtonPress,
+            FakeInput::ButtonRelease(_) => XI1EventCode::DeviceButtonRelease,
+            FakeInput::Motion {
+                ..
+            } => XI1EventCode::DeviceMotionNotify,
+        };
+        let device_id = device.raw_id();
+        if !(2..=0x7f).contains(&device_id) {
+            bail!("cannot fake input for device {}", device_id);
+        }
+        if matches!(input, FakeInput::Motion { .. }) && valuators.is_empty() {
+            bail!("faked device motion requires valuators");
+        }
+        if first_valuator as usize + valuators.len() > u8::MAX as usize {
+            bail!("too many valuators: {} starting at {}", valuators.len(), first_valuator);
+        }
+
+        let valuators = valuators
+            .chunks(6)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let mut values = chunk.to_vec();
+                values.resize(6, 0);
+                FakeDeviceValuator {
+                    event_type: event_start + XI1EventCode::DeviceValuator as u8,
+                    device_id: device_id as u8,
+                    num_valuators: chunk.len() as u8,
+                    first_valuator: first_valuator + (i * 6) as u8,
+                    valuators: values,
+                }
+            })
+            .collect();
+        self.send_fake_input(event_start + event_code as u8, device_id as u8, input, delay, valuators)
+            .await
+    }
+
+    /// Makes this client's requests processed during server grabs by other clients
+    pub async fn xtest_grab_control(&self, impervious: bool) -> Result<()> {
+        send_request_xtest!(
+            self,
+            XTestOpcode::GrabControl,
+            GrabControlRequest {
+                impervious: impervious,
+            }
+        );
+        Ok(())
+    }
+
+    /// Finds a keycode producing `keysym`, and whether shift must be held for it
+    async fn find_keysym(&self, keysym: Keysym) -> Result<Option<(u8, bool)>> {
+        let handshake = self.handshake();
+        let count = handshake.max_keycode - handshake.min_keycode + 1;
+        let mapping = self.legacy_get_keyboard_mapping(handshake.min_keycode, count).await?;
+        for (index, keysyms) in mapping.iter().enumerate() {
+            let keycode = handshake.min_keycode + index as u8;
+            if let Some(column) = keysyms.iter().take(2).position(|x| x.0 == keysym.0) {
+                return Ok(Some((keycode, column == 1)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Presses and releases the key producing `keysym`, holding shift if needed
+    pub async fn type_keysym(&self, keysym: Keysym) -> Result<()> {
+        let (keycode, shifted) = match self.find_keysym(keysym).await? {
+            Some(x) => x,
+            None => bail!("no keycode for keysym {:#x}", keysym.0),
+        };
+        let shift = match shifted {
+            true => match self.find_keysym(SHIFT_L).await? {
+                Some((shift, _)) => Some(shift),
+                None => bail!("no keycode for shift"),
+            },
+            false => None,
+        };
+
+        if let Some(shift) = shift {
+            self.fake_input(FakeInput::KeyPress(shift), 0).await?;
+        }
+        self.fake_input(FakeInput::KeyPress(keycode), 0).await?;
+        self.fake_input(FakeInput::KeyRelease(keycode), 0).await?;
+        if let Some(shift) = shift {
+            self.fake_input(FakeInput::KeyRelease(shift), 0).await?;
+        }
+        Ok(())
+    }
+
+    /// Presses and releases `button` at the current pointer position
+    pub async fn click(&self, button: u8) -> Result<()> {
+        self.fake_input(FakeInput::ButtonPress(button), 0).await?;
+        self.fake_input(FakeInput::ButtonRelease(button), 0).await
+    }
+}
+
+impl<'a> Window<'a> {
+    /// Whether this window's cursor is `cursor`
+    pub async fn xtest_compare_cursor(self, cursor: XTestCursor<'_>) -> Result<bool> {
+        let reply = send_request_xtest!(
+            self.connection,
+            XTestOpcode::CompareCursor,
+            CompareCursorResponse,
+            CompareCursorRequest {
+                window: self.handle,
+                cursor: match cursor {
+                    XTestCursor::None => 0,
+                    XTestCursor::Current => 1,
+                    XTestCursor::Some(cursor) => cursor.handle,
+                },
+            }
+        );
+        Ok(reply.reserved != 0)
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{
+    coding::xtest::{GetVersionRequest, GetVersionResponse},
+    net::{ExtInfo, Extension},
+};
+
+use super::*;
+
+impl X11Connection {
+    pub(crate) async fn enable_xtest(&self) -> Result<()> {
+        // query_extension
+        let queried = self.query_extension(XTEST_EXT_NAME).await?;
+        ensure!(queried.present, "xtest missing on x11 server");
+        self.0.registered_extensions.insert(
+            XTEST_EXT_NAME.to_string(),
+            ExtInfo {
+                extension: Extension::XTest,
+                major_opcode: queried.major_opcode,
+                event_start: queried.first_event,
+                error_start: queried.first_error,
+                event_count: XTEST_EVENT_COUNT,
+                version: None,
+            },
+        );
+
+        // enable extension
+        let reply = send_request_ext!(
+            self,
+            queried.major_opcode,
+            XTestOpcode::GetVersion,
+            GetVersionResponse,
+            GetVersionRequest {
+                major_version: 2,
+                minor_version: 2,
+            }
+        );
+        if reply.reserved != 2 {
+            bail!("unsupported xtest version on server: {}.{}", reply.reserved, reply.minor_version);
+        }
+        self.set_ext_version(XTEST_EXT_NAME, reply.reserved, reply.minor_version);
+        Ok(())
+    }
+}
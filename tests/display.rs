@@ -0,0 +1,32 @@
+use rx11::connection::{DisplayName, DisplayTransport};
+
+#[test]
+fn test_display_names() {
+    let name = DisplayName::parse(":1").unwrap();
+    assert_eq!(name, DisplayName::new("", 1));
+    assert_eq!(name.transport().unwrap(), DisplayTransport::Unix);
+
+    let name = DisplayName::parse("unix:0.1").unwrap();
+    assert_eq!((&*name.host, name.display, name.screen), ("unix", 0, 1));
+    assert_eq!(name.transport().unwrap(), DisplayTransport::Unix);
+
+    let name = DisplayName::parse("tcp/localhost:10.2").unwrap();
+    assert_eq!(name.protocol.as_deref(), Some("tcp"));
+    assert_eq!((&*name.host, name.display, name.screen), ("localhost", 10, 2));
+    assert_eq!(name.transport().unwrap(), DisplayTransport::Tcp);
+
+    let name = DisplayName::parse("[::1]:3").unwrap();
+    assert_eq!((&*name.host, name.display), ("::1", 3));
+    assert_eq!(name.transport().unwrap(), DisplayTransport::Tcp);
+
+    let name = DisplayName::parse("/tmp/.X11-unix/X4").unwrap();
+    assert_eq!(name.socket_path.as_deref(), Some("/tmp/.X11-unix/X4"));
+    assert_eq!(name.display, 4);
+
+    let name = DisplayName::parse("/private/tmp/com.apple.launchd.abc/org.xquartz:0.1").unwrap();
+    assert_eq!(name.socket_path.as_deref(), Some("/private/tmp/com.apple.launchd.abc/org.xquartz:0"));
+    assert_eq!((name.display, name.screen), (0, 1));
+
+    assert!(DisplayName::parse("localhost").is_err());
+    assert!(DisplayName::parse("localhost:x").is_err());
+}